@@ -0,0 +1,72 @@
+//! Verifying key of the eligibility circuit under the development keys in
+//! `zero_knowledge_proofs::dev_eligibility_keys`. Their seed is public, so anyone can forge proofs
+//! this key accepts; it is only compiled in with the `dev-eligibility-key` feature. Regenerate it
+//! with `zkp setup --dev` and `zkp export-verifier --name ELIGIBILITY_DEV_VERIFYING_KEY` whenever
+//! the circuit changes.
+
+use crate::verifier::Groth16VerifyingKey;
+
+pub const ELIGIBILITY_DEV_VERIFYING_KEY: Groth16VerifyingKey = Groth16VerifyingKey {
+    alpha_g1: [
+        0x10, 0x6b, 0x93, 0x2b, 0x1e, 0x8f, 0xf3, 0x2a, 0x9e, 0xc9, 0x7c, 0xe5, 0xdf, 0x8d, 0x5f, 0xc2,
+        0xf7, 0xfc, 0xbb, 0x81, 0xf6, 0x5e, 0x5d, 0x22, 0xaf, 0x11, 0x61, 0x29, 0xf4, 0x09, 0x6e, 0x7e,
+        0x04, 0xa7, 0x8f, 0x45, 0x45, 0x42, 0xc4, 0xc3, 0x97, 0xfb, 0x06, 0x5a, 0xcf, 0x7d, 0x25, 0xfd,
+        0x9d, 0xaa, 0x4f, 0x4a, 0xfc, 0xb5, 0x0f, 0xd5, 0xcf, 0x1f, 0xc3, 0xf3, 0x6a, 0xfa, 0xd2, 0xfa,
+    ],
+    beta_g2: [
+        0x0c, 0xb0, 0xa5, 0x53, 0x32, 0x28, 0x8e, 0x28, 0xa8, 0x6f, 0x1f, 0x51, 0xda, 0x8c, 0xd2, 0x17,
+        0x74, 0x59, 0xab, 0xf0, 0x2c, 0x5b, 0x66, 0x0e, 0xf8, 0x05, 0x60, 0x9b, 0x62, 0x6f, 0x67, 0x68,
+        0x2e, 0x06, 0xab, 0x0f, 0x93, 0x75, 0xf1, 0xa3, 0x54, 0x85, 0xf7, 0xba, 0x89, 0xf2, 0x3d, 0x14,
+        0x68, 0x47, 0x07, 0x22, 0x9a, 0x94, 0x66, 0xfb, 0x1a, 0xb6, 0x89, 0xe9, 0xf8, 0xfb, 0x38, 0x78,
+        0x16, 0x9d, 0x12, 0x2f, 0xe5, 0x1f, 0x46, 0x12, 0xef, 0x33, 0x0d, 0xed, 0x53, 0x6a, 0x83, 0xf5,
+        0x38, 0x7b, 0x8b, 0xf9, 0x43, 0x3c, 0x9a, 0x10, 0x17, 0x9a, 0x21, 0xef, 0x73, 0xe6, 0x67, 0x85,
+        0x2f, 0x33, 0x1a, 0x7a, 0x23, 0x51, 0xad, 0x3e, 0x4f, 0x9f, 0xce, 0x16, 0xe7, 0xc4, 0xd8, 0xcd,
+        0x65, 0xed, 0xc3, 0xc2, 0x44, 0x54, 0xef, 0x6c, 0x4e, 0xec, 0x31, 0xa6, 0x67, 0x66, 0xe2, 0xc4,
+    ],
+    gamma_g2: [
+        0x0f, 0x9e, 0x2c, 0xc4, 0x5b, 0xb8, 0x46, 0x29, 0xbf, 0x31, 0xe0, 0xb4, 0x8c, 0x43, 0x92, 0xa4,
+        0x84, 0xfb, 0x97, 0x94, 0x06, 0xdb, 0xa2, 0x9b, 0x6a, 0x21, 0x73, 0xb0, 0xf5, 0x73, 0xac, 0x7b,
+        0x18, 0x26, 0xf2, 0x36, 0x7c, 0xf6, 0x36, 0x18, 0x67, 0x7f, 0xd9, 0xd2, 0x29, 0x35, 0xad, 0xae,
+        0x13, 0xb8, 0xe6, 0x1b, 0xdb, 0x52, 0x3b, 0xaf, 0x8a, 0xc9, 0xe0, 0x99, 0x1a, 0x0c, 0x4c, 0x3d,
+        0x0a, 0xc0, 0x68, 0xa0, 0xf5, 0x49, 0x49, 0xef, 0x82, 0xa0, 0x2e, 0xf6, 0x49, 0x2f, 0x73, 0x22,
+        0xd8, 0xa8, 0xf6, 0x93, 0xa9, 0xd7, 0x2f, 0x83, 0x67, 0xdc, 0xa9, 0xda, 0xfb, 0x0f, 0x7f, 0x90,
+        0x04, 0x10, 0x41, 0x9c, 0xd7, 0x46, 0xc6, 0x31, 0xba, 0x83, 0x24, 0x95, 0x84, 0xe0, 0xda, 0x03,
+        0x60, 0x90, 0x30, 0x4d, 0x5f, 0xf4, 0x0f, 0x22, 0x2f, 0xe9, 0xb1, 0x8f, 0xfa, 0x07, 0x51, 0x18,
+    ],
+    delta_g2: [
+        0x03, 0xd2, 0xaa, 0xe6, 0xbf, 0x06, 0xec, 0x6e, 0x50, 0x31, 0x3e, 0xe3, 0xe2, 0xe8, 0x3e, 0x01,
+        0xfd, 0x47, 0x22, 0xbc, 0x7b, 0xa2, 0xc2, 0xef, 0x18, 0xcc, 0xad, 0x6f, 0x73, 0xac, 0x33, 0x02,
+        0x08, 0xb3, 0xf9, 0xa9, 0x66, 0xe7, 0x6e, 0xfd, 0x25, 0x6a, 0x80, 0xc7, 0x01, 0x3d, 0x39, 0x91,
+        0x6c, 0x03, 0xc3, 0x7d, 0x98, 0xd6, 0x04, 0xd2, 0xe0, 0xe8, 0xbb, 0xb5, 0xad, 0x25, 0x7c, 0xff,
+        0x17, 0x9c, 0x06, 0x27, 0xc9, 0x3d, 0xeb, 0xc2, 0x91, 0xf6, 0x8e, 0x48, 0x23, 0x61, 0x32, 0xa8,
+        0x73, 0x09, 0x85, 0xa5, 0xbe, 0x1d, 0x5d, 0x9c, 0xc5, 0xe3, 0x7f, 0xfa, 0xed, 0x75, 0xd7, 0x74,
+        0x21, 0x6a, 0x95, 0xf4, 0x61, 0x60, 0xff, 0xcc, 0x96, 0x70, 0xae, 0x8d, 0x3e, 0xca, 0x70, 0x88,
+        0x08, 0xa9, 0xbc, 0x15, 0xd0, 0xe4, 0xa9, 0x5e, 0xd5, 0xa1, 0xa4, 0x3d, 0xe8, 0xb1, 0x5c, 0xb6,
+    ],
+    ic: &[
+        [
+            0x2d, 0x53, 0x8b, 0xa8, 0x98, 0x73, 0x02, 0xe5, 0xe6, 0xde, 0xe8, 0x9d, 0x58, 0xc7, 0x7c, 0x7b,
+            0x2d, 0xb0, 0x6d, 0x4f, 0xb1, 0xfd, 0x25, 0x48, 0xc2, 0xda, 0x29, 0x06, 0x56, 0xa5, 0xfd, 0x7d,
+            0x1d, 0xcc, 0xe6, 0xd8, 0x76, 0xc9, 0xdc, 0xc1, 0xe9, 0xd0, 0xac, 0xe3, 0x78, 0x0b, 0x48, 0x52,
+            0xc4, 0x18, 0x9c, 0x89, 0x2b, 0x66, 0xc0, 0xc8, 0xc9, 0x5e, 0x4f, 0x57, 0x91, 0xd5, 0x61, 0x51,
+        ],
+        [
+            0x0a, 0xd6, 0x17, 0xb5, 0x4a, 0xf9, 0xa1, 0x0d, 0x50, 0xad, 0x99, 0x0f, 0x14, 0x1e, 0x65, 0x32,
+            0xff, 0x1d, 0x5f, 0x03, 0x83, 0xa7, 0xc0, 0xfe, 0x83, 0xde, 0x2c, 0x56, 0x0a, 0x7d, 0x2d, 0x9a,
+            0x01, 0xad, 0xbb, 0x6c, 0x9e, 0x1e, 0x38, 0x1e, 0x2b, 0x21, 0x39, 0xe1, 0x3f, 0xdd, 0x03, 0xbf,
+            0x84, 0xa1, 0x1b, 0xf4, 0x12, 0xdc, 0xe1, 0x38, 0x9b, 0x50, 0x0a, 0x06, 0x46, 0xff, 0x67, 0xd9,
+        ],
+        [
+            0x0d, 0x54, 0x09, 0xb2, 0xa2, 0xc5, 0xe9, 0xc3, 0x69, 0x8a, 0x21, 0x9b, 0x15, 0xee, 0xfb, 0x42,
+            0x8e, 0xbb, 0x7d, 0xee, 0xea, 0xbb, 0x7b, 0x3e, 0x4c, 0x09, 0xa7, 0x21, 0xd7, 0x42, 0x50, 0xc3,
+            0x11, 0x24, 0x4b, 0x06, 0xca, 0xcf, 0x37, 0xaf, 0x67, 0x0f, 0xa3, 0x3c, 0x5e, 0xfe, 0x4b, 0x19,
+            0x53, 0xd2, 0x5e, 0x13, 0x6b, 0x47, 0x15, 0x80, 0xd4, 0x29, 0xfe, 0x40, 0xe0, 0x2b, 0x4c, 0xf1,
+        ],
+        [
+            0x13, 0x4f, 0xc6, 0x99, 0xe0, 0xa5, 0x75, 0x4b, 0xd6, 0xf8, 0x8c, 0x31, 0xad, 0xa3, 0x15, 0xf6,
+            0xc4, 0x84, 0x0e, 0xd1, 0x9a, 0x4b, 0xac, 0x34, 0xe4, 0x18, 0xe1, 0xc7, 0x6b, 0x1f, 0x01, 0x59,
+            0x16, 0x4e, 0x78, 0x51, 0x05, 0xfa, 0x20, 0x62, 0x8f, 0xf4, 0xfb, 0x9b, 0x9a, 0xc8, 0x31, 0x6d,
+            0x21, 0xed, 0x90, 0xd1, 0x6e, 0xf0, 0x37, 0xfa, 0xfd, 0x2a, 0xb4, 0x26, 0xa5, 0x8b, 0x69, 0x4e,
+        ],
+    ],
+};
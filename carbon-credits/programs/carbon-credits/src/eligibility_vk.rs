@@ -0,0 +1,15 @@
+//! Verifying key `create_lend_request` checks eligibility proofs against.
+//!
+//! Whoever knows the toxic waste behind this key can forge proofs, so a production key has to come
+//! from a multi-party `zero_knowledge_proofs::setup::Ceremony` over a public Powers of Tau, exported
+//! with `zkp export-verifier`. None has been run yet, so lending is disabled unless the program is
+//! built with the `dev-eligibility-key` feature, which embeds the publicly derivable development key.
+
+use crate::verifier::Groth16VerifyingKey;
+
+#[cfg(feature = "dev-eligibility-key")]
+pub const ELIGIBILITY_VERIFYING_KEY: Option<&Groth16VerifyingKey> =
+    Some(&crate::eligibility_dev_vk::ELIGIBILITY_DEV_VERIFYING_KEY);
+
+#[cfg(not(feature = "dev-eligibility-key"))]
+pub const ELIGIBILITY_VERIFYING_KEY: Option<&Groth16VerifyingKey> = None;
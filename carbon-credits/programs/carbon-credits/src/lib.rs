@@ -1,8 +1,17 @@
 use anchor_lang::prelude::*;
-use anchor_lang::solana_program::clock::UnixTimestamp;
-use std::convert::TryFrom;
-use std::str::FromStr;
-use zero_knowledge_proofs::eligibility_proof;
+use anchor_lang::system_program;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{
+    self, Burn, CloseAccount, Mint, MintTo, TokenAccount, TokenInterface, TransferChecked,
+};
+
+#[cfg(feature = "dev-eligibility-key")]
+pub mod eligibility_dev_vk;
+pub mod eligibility_vk;
+pub mod poseidon;
+pub mod verifier;
+
+use eligibility_vk::ELIGIBILITY_VERIFYING_KEY;
 
 declare_id!("A5zmaYX8z3vQVh8cf1aByvvURTGqxitoH9jZAHpN7C5n");
 
@@ -17,6 +26,16 @@ pub mod carbon_credit {
             owner: config.owner,
             voting_period: config.voting_period,
             total_carbon_credits: config.total_carbon_credits,
+            reputation_weight: config.reputation_weight,
+            stake_weight: config.stake_weight,
+            quorum_base: config.quorum_base,
+            quorum_per_credit_bps: config.quorum_per_credit_bps,
+            min_voters: config.min_voters,
+            approval_bps: config.approval_bps,
+            supermajority_bps: config.supermajority_bps,
+            supermajority_claim_size: config.supermajority_claim_size,
+            credit_mint: config.credit_mint,
+            eligibility_threshold: config.eligibility_threshold,
         })
     }
 
@@ -24,10 +43,10 @@ pub mod carbon_credit {
         let claim = &ctx.accounts.claim;
         let clock = Clock::get()?;
         
-        let (yes_votes, no_votes) = if clock.unix_timestamp as u64 > claim.voting_end_time {
-            (claim.yes_votes, claim.no_votes)
+        let (yes_votes, no_votes, yes_weight, no_weight) = if clock.unix_timestamp as u64 > claim.voting_end_time {
+            (claim.yes_votes, claim.no_votes, claim.yes_weight, claim.no_weight)
         } else {
-            (0, 0)
+            (0, 0, 0, 0)
         };
         
         Ok(ClaimResponse {
@@ -43,6 +62,8 @@ pub mod carbon_credit {
             voting_end_time: claim.voting_end_time,
             yes_votes,
             no_votes,
+            yes_weight,
+            no_weight,
         })
     }
 
@@ -58,6 +79,8 @@ pub mod carbon_credit {
             total_returned: org_info.total_returned,
             name: org_info.name.clone(),
             emissions: org_info.emissions,
+            retired_credits: org_info.retired_credits,
+            net_emissions: org_info.net_emissions(),
         })
     }
     pub fn get_total_carbon_credits(ctx: Context<GetTotalCarbonCredits>) -> Result<TotalCarbonCreditsResponse> {
@@ -69,35 +92,174 @@ pub mod carbon_credit {
 
 
     pub fn initialize(ctx: Context<Initialize>, voting_period: u64) -> Result<()> {
+        ctx.accounts.config.set_inner(Config::new(*ctx.accounts.owner.key, voting_period, ctx.bumps.config));
+        Ok(())
+    }
+
+    pub fn set_vote_weighting(ctx: Context<SetVoteWeighting>, reputation_weight: u64, stake_weight: u64) -> Result<()> {
+        if reputation_weight == 0 && stake_weight == 0 {
+            return Err(ErrorCode::InvalidVoteWeighting.into());
+        }
+
+        let config = &mut ctx.accounts.config;
+        config.reputation_weight = reputation_weight;
+        config.stake_weight = stake_weight;
+        Ok(())
+    }
+
+    pub fn set_voting_thresholds(
+        ctx: Context<SetVotingThresholds>,
+        quorum_base: u64,
+        quorum_per_credit_bps: u64,
+        min_voters: u64,
+        approval_bps: u64,
+        supermajority_bps: u64,
+        supermajority_claim_size: u64,
+    ) -> Result<()> {
+        if approval_bps > BPS_DENOMINATOR || supermajority_bps > BPS_DENOMINATOR || supermajority_bps < approval_bps {
+            return Err(ErrorCode::InvalidVotingThresholds.into());
+        }
+
         let config = &mut ctx.accounts.config;
-        config.owner = *ctx.accounts.owner.key;
-        config.voting_period = voting_period;
-        config.total_carbon_credits = 0;
+        config.quorum_base = quorum_base;
+        config.quorum_per_credit_bps = quorum_per_credit_bps;
+        config.min_voters = min_voters;
+        config.approval_bps = approval_bps;
+        config.supermajority_bps = supermajority_bps;
+        config.supermajority_claim_size = supermajority_claim_size;
+        Ok(())
+    }
+
+    /// Score, `reputation * credit balance + total_returned`, a borrower must prove to reach before
+    /// `create_lend_request` accepts their request.
+    pub fn set_eligibility_threshold(ctx: Context<SetEligibilityThreshold>, threshold: u64) -> Result<()> {
+        ctx.accounts.config.eligibility_threshold = threshold;
+        Ok(())
+    }
+
+    pub fn initialize_credit_mint(ctx: Context<InitializeCreditMint>) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        config.credit_mint = ctx.accounts.credit_mint.key();
+        config.authority_bump = ctx.bumps.program_authority;
+        Ok(())
+    }
+
+    pub fn cast_vote(ctx: Context<CastVote>, _claim_id: u64, vote: VoteOption, stake: u64) -> Result<()> {
+        let claim = &mut ctx.accounts.claim;
+        let vote_record = &mut ctx.accounts.vote_record;
+        let voter_info = &ctx.accounts.voter_info;
+        let clock = Clock::get()?;
+
+        claim.check_vote(ctx.accounts.voter.key, vote_record, clock.unix_timestamp as u64)?;
+
+        if ctx.accounts.voter_token_account.amount < stake {
+            return Err(ErrorCode::NotEnoughCredits.into());
+        }
+
+        let weight = ctx.accounts.config.vote_weight(voter_info.reputation_score, stake)?;
+        if weight == 0 {
+            return Err(ErrorCode::NoVotingWeight.into());
+        }
+
+        // Staked credits stay locked in the program escrow until the claim is finalized.
+        if stake > 0 {
+            transfer_tokens(
+                &ctx.accounts.token_program,
+                &ctx.accounts.credit_mint,
+                &ctx.accounts.voter_token_account,
+                &ctx.accounts.credit_escrow,
+                &ctx.accounts.voter.to_account_info(),
+                &[],
+                stake,
+            )?;
+        }
+
+        claim.tally_vote(&vote, weight)?;
+
+        vote_record.voter = *ctx.accounts.voter.key;
+        vote_record.vote = vote;
+        vote_record.timestamp = clock.unix_timestamp as u64;
+        vote_record.weight = weight;
+        vote_record.stake = stake;
+
         Ok(())
     }
- 
-    pub fn finalize_voting(ctx: Context<FinalizeVoting>, claim_id: u64) -> Result<()> {
+
+    pub fn finalize_voting(ctx: Context<FinalizeVoting>, _claim_id: u64) -> Result<()> {
         let claim = &mut ctx.accounts.claim;
         let clock = Clock::get()?;
         
+        if claim.status != ClaimStatus::Active {
+            return Err(ErrorCode::VotingEnded.into());
+        }
+
         if clock.unix_timestamp as u64 <= claim.voting_end_time {
             return Err(ErrorCode::VotingNotEnded.into());
         }
         
-        let approved = claim.yes_votes >= claim.no_votes;
-        claim.status = if approved { ClaimStatus::Approved } else { ClaimStatus::Rejected };
+        claim.status = ctx.accounts.config.claim_outcome(claim);
+        ctx.accounts.organization_info.record_claim_outcome(&claim.status);
         
-        if approved {
-            let mut org_info = &mut ctx.accounts.organization_info;
-            org_info.carbon_credits += claim.demanded_tokens;
-            
+        if claim.status == ClaimStatus::Approved {
+            mint_credits(
+                &ctx.accounts.token_program,
+                &ctx.accounts.credit_mint,
+                &ctx.accounts.organization_token_account,
+                &ctx.accounts.program_authority,
+                ctx.accounts.config.authority_bump,
+                claim.demanded_tokens,
+            )?;
+
             let config = &mut ctx.accounts.config;
-            config.total_carbon_credits += claim.demanded_tokens;
+            config.total_carbon_credits =
+                config.total_carbon_credits.checked_add(claim.demanded_tokens).ok_or(ErrorCode::MathOverflow)?;
         }
         
         Ok(())
     }
 
+    pub fn release_vote_stake(ctx: Context<ReleaseVoteStake>, _claim_id: u64) -> Result<()> {
+        if ctx.accounts.claim.status == ClaimStatus::Active {
+            return Err(ErrorCode::VotingNotEnded.into());
+        }
+
+        let stake = ctx.accounts.vote_record.stake;
+        if stake > 0 {
+            transfer_tokens(
+                &ctx.accounts.token_program,
+                &ctx.accounts.credit_mint,
+                &ctx.accounts.credit_escrow,
+                &ctx.accounts.voter_token_account,
+                &ctx.accounts.program_authority,
+                &[&[b"authority", &[ctx.accounts.config.authority_bump]]],
+                stake,
+            )?;
+        }
+        ctx.accounts.vote_record.stake = 0;
+
+        Ok(())
+    }
+
+    /// Mint the credits an organization holds as a bare `OrganizationInfo` balance into its token account.
+    pub fn convert_legacy_credits(ctx: Context<ConvertLegacyCredits>) -> Result<()> {
+        let amount = ctx.accounts.organization_info.carbon_credits;
+        if amount == 0 {
+            return Err(ErrorCode::NotEnoughCredits.into());
+        }
+
+        mint_credits(
+            &ctx.accounts.token_program,
+            &ctx.accounts.credit_mint,
+            &ctx.accounts.organization_token_account,
+            &ctx.accounts.program_authority,
+            ctx.accounts.config.authority_bump,
+            amount,
+        )?;
+        ctx.accounts.organization_info.carbon_credits = 0;
+
+        Ok(())
+    }
+
     pub fn create_claim(
         ctx: Context<CreateClaim>,
         longitudes: Vec<String>,
@@ -122,16 +284,41 @@ pub mod carbon_credit {
         claim.voting_end_time = Clock::get()?.unix_timestamp as u64 + config.voting_period;
         claim.yes_votes = 0;
         claim.no_votes = 0;
+        claim.yes_weight = 0;
+        claim.no_weight = 0;
+        claim.retired = 0;
         
         ctx.accounts.claim_counter.count += 1;
         
         Ok(())
     }
 
+    /// `proof` comes from `zero_knowledge_proofs::eligibility_proof`, run by the borrower off-chain,
+    /// and shows that their score reaches the configured threshold without revealing it. The public
+    /// inputs are the threshold, a commitment to the signer's key and a commitment to the signer's
+    /// credit token balance, total returned and reputation, all rebuilt here, so a proof only counts
+    /// for the record on-chain and the signer it was made for. A proof that does not verify fails with
+    /// `BorrowerNotEligible`. It is only as sound as the setup behind `ELIGIBILITY_VERIFYING_KEY`; see
+    /// `eligibility_vk`.
     pub fn create_lend_request(
         ctx: Context<CreateLendRequest>,
         amount: u64,
+        proof: [u8; verifier::PROOF_LEN],
     ) -> Result<()> {
+        let vk = ELIGIBILITY_VERIFYING_KEY.ok_or(ErrorCode::EligibilityKeyUnavailable)?;
+        let threshold = ctx.accounts.config.eligibility_threshold;
+        let borrower_info = &ctx.accounts.borrower_info;
+        let commitment = poseidon::key_commitment(&ctx.accounts.borrower.key().to_bytes())
+            .ok_or(ErrorCode::BorrowerNotEligible)?;
+        let record_commitment = poseidon::record_commitment(
+            borrower_info.total_returned,
+            ctx.accounts.borrower_token_account.amount,
+            borrower_info.reputation_score,
+        )
+        .ok_or(ErrorCode::BorrowerNotEligible)?;
+        let public_inputs = [verifier::u64_to_public_input(threshold), commitment, record_commitment];
+        verifier::verify(vk, &proof, &public_inputs).map_err(ErrorCode::from)?;
+
         let lend_request = &mut ctx.accounts.lend_request;
         let clock = Clock::get()?;
         
@@ -141,29 +328,16 @@ pub mod carbon_credit {
         lend_request.amount = amount;
         lend_request.status = LentStatus::Active;
         lend_request.time = clock.unix_timestamp as u64;
-        let borrower_info = &ctx.accounts.borrower_info;
-        let lender_info = &ctx.accounts.lender_info;
-        let (eligibility_score, proof_data) = eligibility_proof(
-            borrower_info.emissions as u32,                
-            borrower_info.total_returned as u32,                
-            borrower_info.total_borrowed as u32,             
-            borrower_info.debt as u32,                 
-            borrower_info.carbon_credits as u32,      
-            borrower_info.reputation_score as u32,     
-            lender_info.carbon_credits as u32,           
-            lender_info.debt as u32,                         
-        );
-        
-        // Ensure score is within valid range (0-100)
-        let final_score = (eligibility_score.abs() % 101) as u64;
-        lend_request.eligibility_score = final_score;
-        lend_request.proof_data = format!("zk_proof_len_{}", proof_data.len());
+        lend_request.eligibility_score = threshold;
+        lend_request.proof_data = proof.to_vec();
+        lend_request.record_commitment = record_commitment;
+        lend_request.repaid = 0;
         ctx.accounts.lend_request_counter.count += 1;
         
         Ok(())
     }
 
-    pub fn lend_tokens(ctx: Context<LendTokens>, lend_request_id: u64, response: String) -> Result<()> {
+    pub fn lend_tokens(ctx: Context<LendTokens>, _lend_request_id: u64, response: String) -> Result<()> {
         let lend_request = &mut ctx.accounts.lend_request;
         
         if lend_request.lender != *ctx.accounts.lender.key {
@@ -184,41 +358,63 @@ pub mod carbon_credit {
             return Ok(());
         }
         
-        let mut lender_info = &mut ctx.accounts.lender_info;
-        let mut borrower_info = &mut ctx.accounts.borrower_info;
-        
-        if lender_info.carbon_credits < lend_request.amount {
+        if ctx.accounts.lender_token_account.amount < lend_request.amount {
             return Err(ErrorCode::NotEnoughCredits.into());
         }
         
-        lender_info.carbon_credits -= lend_request.amount;
-        borrower_info.carbon_credits += lend_request.amount;
-        borrower_info.debt += lend_request.amount;
-        borrower_info.times_borrowed += 1;
-        borrower_info.total_borrowed += lend_request.amount;
+        transfer_tokens(
+            &ctx.accounts.token_program,
+            &ctx.accounts.credit_mint,
+            &ctx.accounts.lender_token_account,
+            &ctx.accounts.borrower_token_account,
+            &ctx.accounts.lender.to_account_info(),
+            &[],
+            lend_request.amount,
+        )?;
+
+        let borrower_info = &mut ctx.accounts.borrower_info;
+        borrower_info.debt = borrower_info.debt.checked_add(lend_request.amount).ok_or(ErrorCode::MathOverflow)?;
+        borrower_info.times_borrowed = borrower_info.times_borrowed.checked_add(1).ok_or(ErrorCode::MathOverflow)?;
+        borrower_info.total_borrowed =
+            borrower_info.total_borrowed.checked_add(lend_request.amount).ok_or(ErrorCode::MathOverflow)?;
         
         lend_request.status = LentStatus::Approved;
         
         Ok(())
     }
 
+    /// Repay part of `lend_request` to its lender. Repayments are capped at what is still owed on that
+    /// loan, which is marked `Repaid` once settled.
     pub fn repay_tokens(ctx: Context<RepayTokens>, amount: u64) -> Result<()> {
-        let mut borrower_info = &mut ctx.accounts.borrower_info;
-        let mut lender_info = &mut ctx.accounts.lender_info;
-        
-        if borrower_info.carbon_credits < amount {
+        ctx.accounts.lend_request.record_repayment(amount)?;
+
+        if ctx.accounts.borrower_token_account.amount < amount {
             return Err(ErrorCode::NotEnoughCredits.into());
         }
         
-        if borrower_info.debt < amount {
-            return Err(ErrorCode::NotEnoughCredits.into());
+        transfer_tokens(
+            &ctx.accounts.token_program,
+            &ctx.accounts.credit_mint,
+            &ctx.accounts.borrower_token_account,
+            &ctx.accounts.lender_token_account,
+            &ctx.accounts.borrower.to_account_info(),
+            &[],
+            amount,
+        )?;
+
+        let borrower_info = &mut ctx.accounts.borrower_info;
+        borrower_info.debt = borrower_info.debt.checked_sub(amount).ok_or(ErrorCode::MathOverflow)?;
+        borrower_info.total_returned = borrower_info.total_returned.checked_add(amount).ok_or(ErrorCode::MathOverflow)?;
+        if ctx.accounts.lend_request.status == LentStatus::Repaid {
+            borrower_info.record_repaid_loan();
         }
         
-        borrower_info.carbon_credits -= amount;
-        borrower_info.debt -= amount;
-        borrower_info.total_returned += amount;
-        lender_info.carbon_credits += amount;
-        
+        Ok(())
+    }
+
+    /// Create the signer's `OrganizationInfo`. Only registered organizations can vote on claims.
+    pub fn register_organization(ctx: Context<RegisterOrganization>, name: String) -> Result<()> {
+        ctx.accounts.organization_info.name = name;
         Ok(())
     }
 
@@ -234,6 +430,319 @@ pub mod carbon_credit {
         Ok(())
     }
 
+    /// Burn credits against the organization's emissions. The approved claims the credits came from are
+    /// passed as writable remaining accounts, each once and each the organization's own, and recorded on
+    /// the certificate. The amount is drawn from those claims in order, so together they must have that
+    /// many credits not yet cited by an earlier retirement.
+    pub fn retire_credits<'info>(
+        ctx: Context<'_, '_, 'info, 'info, RetireCredits<'info>>,
+        amount: u64,
+        beneficiary: Pubkey,
+        reason: Option<String>,
+    ) -> Result<()> {
+        if amount == 0 {
+            return Err(ErrorCode::InvalidAmount.into());
+        }
+
+        if ctx.accounts.organization_token_account.amount < amount {
+            return Err(ErrorCode::NotEnoughCredits.into());
+        }
+
+        if reason.as_ref().is_some_and(|reason| reason.len() > RetirementCertificate::MAX_REASON_LEN) {
+            return Err(ErrorCode::ReasonTooLong.into());
+        }
+
+        if ctx.remaining_accounts.len() > RetirementCertificate::MAX_CLAIMS {
+            return Err(ErrorCode::TooManyClaims.into());
+        }
+
+        let mut claims = Vec::new();
+        for (index, claim_account) in ctx.remaining_accounts.iter().enumerate() {
+            if ctx.remaining_accounts[..index].iter().any(|earlier| earlier.key == claim_account.key) {
+                return Err(ErrorCode::DuplicateClaim.into());
+            }
+            if !claim_account.is_writable {
+                return Err(anchor_lang::error::ErrorCode::AccountNotMutable.into());
+            }
+            let claim = Account::<Claim>::try_from(claim_account)?;
+            if claim.organization != ctx.accounts.organization.key() {
+                return Err(ErrorCode::ClaimNotOwned.into());
+            }
+            if claim.status != ClaimStatus::Approved {
+                return Err(ErrorCode::ClaimNotApproved.into());
+            }
+            claims.push(claim);
+        }
+
+        draw_retirement(claims.iter_mut().map(|claim| &mut **claim), amount)?;
+        for claim in &claims {
+            claim.exit(ctx.program_id)?;
+        }
+        let claim_ids = claims.iter().map(|claim| claim.id).collect();
+
+        token_interface::burn(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Burn {
+                    mint: ctx.accounts.credit_mint.to_account_info(),
+                    from: ctx.accounts.organization_token_account.to_account_info(),
+                    authority: ctx.accounts.organization.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+
+        let config = &mut ctx.accounts.config;
+        config.total_carbon_credits = config.total_carbon_credits.saturating_sub(amount);
+
+        let org_info = &mut ctx.accounts.organization_info;
+        let certificate = &mut ctx.accounts.certificate;
+        certificate.id = org_info.retirement_count;
+        certificate.organization = *ctx.accounts.organization.key;
+        certificate.beneficiary = beneficiary;
+        certificate.amount = amount;
+        certificate.claim_ids = claim_ids;
+        certificate.timestamp = Clock::get()?.unix_timestamp as u64;
+        certificate.reason = reason;
+
+        org_info.retired_credits += amount;
+        org_info.retirement_count += 1;
+
+        Ok(())
+    }
+
+    /// Open an order on the credit book. Sell orders escrow credits; buy orders escrow the quote, either
+    /// lamports on the order account (SOL, no `quote_mint`) or tokens in the order escrow. Credits trade
+    /// as tokens of the credit mint, so the token accounts are the ledger; `OrganizationInfo` holds no
+    /// tradeable balance.
+    pub fn place_order(
+        ctx: Context<PlaceOrder>,
+        order_id: u64,
+        side: OrderSide,
+        price: u64,
+        amount: u64,
+        expires_in: u64,
+    ) -> Result<()> {
+        if price == 0 || amount == 0 || expires_in == 0 {
+            return Err(ErrorCode::InvalidAmount.into());
+        }
+
+        let quote_mint = ctx.accounts.quote_mint.as_ref().map(|mint| mint.key());
+        let expected_mint = Order::escrow_mint_for(&side, ctx.accounts.credit_mint.key(), quote_mint);
+        let escrow_amount = match side {
+            OrderSide::Sell => amount,
+            OrderSide::Buy => order_cost(price, amount)?,
+        };
+
+        if side == OrderSide::Buy && quote_mint.is_none() {
+            if ctx.accounts.escrow.is_some() {
+                return Err(ErrorCode::InvalidOrderAccounts.into());
+            }
+
+            system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.maker.to_account_info(),
+                        to: ctx.accounts.order.to_account_info(),
+                    },
+                ),
+                escrow_amount,
+            )?;
+        } else {
+            let (Some(escrow), Some(maker_token_account)) = (&ctx.accounts.escrow, &ctx.accounts.maker_token_account) else {
+                return Err(ErrorCode::InvalidOrderAccounts.into());
+            };
+            if Some(ctx.accounts.escrow_mint.key()) != expected_mint {
+                return Err(ErrorCode::InvalidOrderAccounts.into());
+            }
+            if maker_token_account.amount < escrow_amount {
+                return Err(ErrorCode::NotEnoughCredits.into());
+            }
+
+            transfer_tokens(
+                &ctx.accounts.token_program,
+                &ctx.accounts.escrow_mint,
+                maker_token_account,
+                escrow,
+                &ctx.accounts.maker.to_account_info(),
+                &[],
+                escrow_amount,
+            )?;
+        }
+
+        let now = Clock::get()?.unix_timestamp as u64;
+        let order = &mut ctx.accounts.order;
+        order.id = order_id;
+        order.maker = *ctx.accounts.maker.key;
+        order.side = side;
+        order.quote_mint = quote_mint;
+        order.price = price;
+        order.amount = amount;
+        order.remaining = amount;
+        order.created_at = now;
+        order.expires_at = now.checked_add(expires_in).ok_or(ErrorCode::MathOverflow)?;
+        order.status = OrderStatus::Open;
+
+        Ok(())
+    }
+
+    /// Take up to `amount` credits from an open order. Both legs settle in this instruction as token
+    /// (or lamport) transfers, so the credit and quote balances move together or not at all; the token
+    /// accounts are the ledger and `OrganizationInfo` is not touched.
+    pub fn fill_order(ctx: Context<FillOrder>, _order_id: u64, amount: u64) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp as u64;
+        let cost = ctx.accounts.order.fill(amount, now)?;
+        let order = &ctx.accounts.order;
+
+        if ctx.accounts.quote_mint.as_ref().map(|mint| mint.key()) != order.quote_mint {
+            return Err(ErrorCode::InvalidOrderAccounts.into());
+        }
+
+        let expected_escrow_mint = Order::escrow_mint_for(&order.side, ctx.accounts.credit_mint.key(), order.quote_mint);
+        if ctx.accounts.escrow.as_ref().map(|escrow| escrow.mint) != expected_escrow_mint {
+            return Err(ErrorCode::InvalidOrderAccounts.into());
+        }
+
+        let quote_accounts = [&ctx.accounts.taker_quote_account, &ctx.accounts.maker_quote_account];
+        if quote_accounts.iter().any(|account| account.as_ref().is_some_and(|account| Some(account.mint) != order.quote_mint)) {
+            return Err(ErrorCode::InvalidOrderAccounts.into());
+        }
+
+        let signer_seeds: &[&[&[u8]]] = &[&[b"authority", &[ctx.accounts.config.authority_bump]]];
+
+        match order.side {
+            OrderSide::Sell => {
+                let escrow = ctx.accounts.escrow.as_ref().ok_or(ErrorCode::InvalidOrderAccounts)?;
+                transfer_tokens(
+                    &ctx.accounts.token_program,
+                    &ctx.accounts.credit_mint,
+                    escrow,
+                    &ctx.accounts.taker_credit_account,
+                    &ctx.accounts.program_authority,
+                    signer_seeds,
+                    amount,
+                )?;
+
+                match &ctx.accounts.quote_mint {
+                    None => system_program::transfer(
+                        CpiContext::new(
+                            ctx.accounts.system_program.to_account_info(),
+                            system_program::Transfer {
+                                from: ctx.accounts.taker.to_account_info(),
+                                to: ctx.accounts.maker.to_account_info(),
+                            },
+                        ),
+                        cost,
+                    )?,
+                    Some(quote_mint) => {
+                        let (Some(taker_quote_account), Some(maker_quote_account)) =
+                            (&ctx.accounts.taker_quote_account, &ctx.accounts.maker_quote_account)
+                        else {
+                            return Err(ErrorCode::InvalidOrderAccounts.into());
+                        };
+                        if maker_quote_account.owner != order.maker {
+                            return Err(ErrorCode::InvalidOrderAccounts.into());
+                        }
+                        transfer_tokens(
+                            &ctx.accounts.token_program,
+                            quote_mint,
+                            taker_quote_account,
+                            maker_quote_account,
+                            &ctx.accounts.taker.to_account_info(),
+                            &[],
+                            cost,
+                        )?;
+                    }
+                }
+            }
+            OrderSide::Buy => {
+                let maker_credit_account =
+                    ctx.accounts.maker_credit_account.as_ref().ok_or(ErrorCode::InvalidOrderAccounts)?;
+                transfer_tokens(
+                    &ctx.accounts.token_program,
+                    &ctx.accounts.credit_mint,
+                    &ctx.accounts.taker_credit_account,
+                    maker_credit_account,
+                    &ctx.accounts.taker.to_account_info(),
+                    &[],
+                    amount,
+                )?;
+
+                match &ctx.accounts.quote_mint {
+                    None => {
+                        // The order account is program-owned, so its escrowed lamports can be moved directly.
+                        ctx.accounts.order.sub_lamports(cost)?;
+                        ctx.accounts.taker.add_lamports(cost)?;
+                    }
+                    Some(quote_mint) => {
+                        let (Some(escrow), Some(taker_quote_account)) =
+                            (&ctx.accounts.escrow, &ctx.accounts.taker_quote_account)
+                        else {
+                            return Err(ErrorCode::InvalidOrderAccounts.into());
+                        };
+                        transfer_tokens(
+                            &ctx.accounts.token_program,
+                            quote_mint,
+                            escrow,
+                            taker_quote_account,
+                            &ctx.accounts.program_authority,
+                            signer_seeds,
+                            cost,
+                        )?;
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Close an order, returning whatever is left in escrow to the maker. Works on open, expired and
+    /// filled orders alike; SOL escrow and rent come back when the order account closes.
+    pub fn cancel_order(ctx: Context<CancelOrder>, _order_id: u64) -> Result<()> {
+        let order = &ctx.accounts.order;
+        let Some(escrow) = &ctx.accounts.escrow else {
+            // Only SOL-priced buys keep their escrow on the order account; every other order has a token
+            // escrow that must be refunded before the order can go.
+            if order.side == OrderSide::Buy && order.quote_mint.is_none() {
+                return Ok(());
+            }
+            return Err(ErrorCode::InvalidOrderAccounts.into());
+        };
+        let signer_seeds: &[&[&[u8]]] = &[&[b"authority", &[ctx.accounts.config.authority_bump]]];
+
+        if escrow.amount > 0 {
+            let (Some(escrow_mint), Some(maker_token_account)) =
+                (&ctx.accounts.escrow_mint, &ctx.accounts.maker_token_account)
+            else {
+                return Err(ErrorCode::InvalidOrderAccounts.into());
+            };
+            if escrow_mint.key() != escrow.mint || maker_token_account.mint != escrow.mint {
+                return Err(ErrorCode::InvalidOrderAccounts.into());
+            }
+            transfer_tokens(
+                &ctx.accounts.token_program,
+                escrow_mint,
+                escrow,
+                maker_token_account,
+                &ctx.accounts.program_authority,
+                signer_seeds,
+                escrow.amount,
+            )?;
+        }
+
+        token_interface::close_account(CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            CloseAccount {
+                account: escrow.to_account_info(),
+                destination: ctx.accounts.maker.to_account_info(),
+                authority: ctx.accounts.program_authority.to_account_info(),
+            },
+            signer_seeds,
+        ))
+    }
+
    pub fn get_all_claims<'info>(
         ctx: Context<'_, '_, 'info, 'info, GetAllClaims<'info>>
     ) -> Result<AllClaimsResponse> {
@@ -243,10 +752,10 @@ pub mod carbon_credit {
         for claim_account in claims.iter() {
             if let Ok(claim) = Account::<Claim>::try_from(claim_account) {
                 let clock = Clock::get()?;
-                let (yes_votes, no_votes) = if clock.unix_timestamp as u64 > claim.voting_end_time {
-                    (claim.yes_votes, claim.no_votes)
+                let (yes_votes, no_votes, yes_weight, no_weight) = if clock.unix_timestamp as u64 > claim.voting_end_time {
+                    (claim.yes_votes, claim.no_votes, claim.yes_weight, claim.no_weight)
                 } else {
-                    (0, 0)
+                    (0, 0, 0, 0)
                 };
                 
                 claim_responses.push(ClaimResponse {
@@ -262,6 +771,8 @@ pub mod carbon_credit {
                     voting_end_time: claim.voting_end_time,
                     yes_votes,
                     no_votes,
+                    yes_weight,
+                    no_weight,
                 });
             }
         }
@@ -273,9 +784,83 @@ pub mod carbon_credit {
 
 }
 
+fn mint_credits<'info>(
+    token_program: &Interface<'info, TokenInterface>,
+    credit_mint: &InterfaceAccount<'info, Mint>,
+    to: &InterfaceAccount<'info, TokenAccount>,
+    program_authority: &UncheckedAccount<'info>,
+    authority_bump: u8,
+    amount: u64,
+) -> Result<()> {
+    let signer_seeds: &[&[&[u8]]] = &[&[b"authority", &[authority_bump]]];
+    token_interface::mint_to(
+        CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            MintTo {
+                mint: credit_mint.to_account_info(),
+                to: to.to_account_info(),
+                authority: program_authority.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        amount,
+    )
+}
+
+/// Count `amount` retired credits against `claims`, taking as much as each has left before moving to
+/// the next. Fails with `ClaimCreditsExhausted`, leaving the claims untouched, if they cannot cover it.
+pub fn draw_retirement<'a>(claims: impl IntoIterator<Item = &'a mut Claim>, amount: u64) -> Result<()> {
+    let claims: Vec<&mut Claim> = claims.into_iter().collect();
+    let available = claims.iter().fold(0u64, |total, claim| total.saturating_add(claim.retirable()));
+    if available < amount {
+        return Err(ErrorCode::ClaimCreditsExhausted.into());
+    }
+
+    let mut left = amount;
+    for claim in claims {
+        let drawn = claim.retirable().min(left);
+        claim.retired += drawn;
+        left -= drawn;
+    }
+    Ok(())
+}
+
+fn order_cost(price: u64, amount: u64) -> Result<u64> {
+    price.checked_mul(amount).ok_or_else(|| ErrorCode::MathOverflow.into())
+}
+
+fn checked_tally(tally: u64, amount: u64) -> Result<u64> {
+    tally.checked_add(amount).ok_or_else(|| ErrorCode::MathOverflow.into())
+}
+
+fn transfer_tokens<'info>(
+    token_program: &Interface<'info, TokenInterface>,
+    credit_mint: &InterfaceAccount<'info, Mint>,
+    from: &InterfaceAccount<'info, TokenAccount>,
+    to: &InterfaceAccount<'info, TokenAccount>,
+    authority: &AccountInfo<'info>,
+    signer_seeds: &[&[&[u8]]],
+    amount: u64,
+) -> Result<()> {
+    token_interface::transfer_checked(
+        CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            TransferChecked {
+                from: from.to_account_info(),
+                mint: credit_mint.to_account_info(),
+                to: to.to_account_info(),
+                authority: authority.clone(),
+            },
+            signer_seeds,
+        ),
+        amount,
+        credit_mint.decimals,
+    )
+}
+
 #[derive(Accounts)]
 pub struct Initialize<'info> {
-    #[account(init, payer = owner, space = 8 + Config::LEN)]
+    #[account(init, payer = owner, space = 8 + Config::LEN, seeds = [b"config"], bump)]
     pub config: Account<'info, Config>,
     #[account(mut)]
     pub owner: Signer<'info>,
@@ -288,7 +873,7 @@ pub struct Initialize<'info> {
 
 #[derive(Accounts)]
 pub struct CreateClaim<'info> {
-    #[account(mut)]
+    #[account(mut, seeds = [b"config"], bump = config.bump)]
     pub config: Account<'info, Config>,
     #[account(mut)]
     pub claim_counter: Account<'info, Counter>,
@@ -307,39 +892,194 @@ pub struct CreateClaim<'info> {
     pub organization_info: Account<'info, OrganizationInfo>,
 }
 
+#[derive(Accounts)]
+pub struct InitializeCreditMint<'info> {
+    #[account(mut, has_one = owner, seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    /// CHECK: PDA that signs for the credit mint and the escrow; it holds no data
+    #[account(seeds = [b"authority"], bump)]
+    pub program_authority: UncheckedAccount<'info>,
+    #[account(
+        init,
+        payer = owner,
+        seeds = [b"credit_mint"],
+        bump,
+        mint::decimals = 0,
+        mint::authority = program_authority,
+        mint::token_program = token_program
+    )]
+    pub credit_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        init,
+        payer = owner,
+        seeds = [b"credit_escrow"],
+        bump,
+        token::mint = credit_mint,
+        token::authority = program_authority,
+        token::token_program = token_program
+    )]
+    pub credit_escrow: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
 #[derive(Accounts)]
 #[instruction(claim_id: u64)]
 pub struct CastVote<'info> {
-    #[account(mut)]
+    #[account(has_one = credit_mint, seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(mut, constraint = claim.id == claim_id)]
     pub claim: Account<'info, Claim>,
-    #[account(init, payer = voter, space = 8 + VoteRecord::LEN)]
+    #[account(
+        init_if_needed,
+        payer = voter,
+        space = 8 + VoteRecord::LEN,
+        seeds = [b"vote", claim.key().as_ref(), voter.key().as_ref()],
+        bump
+    )]
     pub vote_record: Account<'info, VoteRecord>,
     #[account(mut)]
     pub voter: Signer<'info>,
     pub system_program: Program<'info, System>,
     #[account(
-        init_if_needed,
-        payer = voter,
-        space = 8 + OrganizationInfo::LEN,
         seeds = [b"organization", voter.key().as_ref()],
         bump
     )]
     pub voter_info: Account<'info, OrganizationInfo>,
+    pub credit_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        token::mint = credit_mint,
+        token::authority = voter,
+        token::token_program = token_program
+    )]
+    pub voter_token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut, seeds = [b"credit_escrow"], bump)]
+    pub credit_escrow: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>,
 }
 
 #[derive(Accounts)]
 #[instruction(claim_id: u64)]
-pub struct FinalizeVoting<'info> {
-    #[account(mut)]
+pub struct ReleaseVoteStake<'info> {
+    #[account(has_one = credit_mint, seeds = [b"config"], bump = config.bump)]
     pub config: Account<'info, Config>,
-    #[account(mut)]
+    #[account(constraint = claim.id == claim_id)]
     pub claim: Account<'info, Claim>,
+    #[account(
+        mut,
+        seeds = [b"vote", claim.key().as_ref(), voter.key().as_ref()],
+        bump
+    )]
+    pub vote_record: Account<'info, VoteRecord>,
+    pub voter: Signer<'info>,
+    /// CHECK: PDA that signs for the credit mint and the escrow; it holds no data
+    #[account(seeds = [b"authority"], bump = config.authority_bump)]
+    pub program_authority: UncheckedAccount<'info>,
+    pub credit_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        token::mint = credit_mint,
+        token::authority = voter,
+        token::token_program = token_program
+    )]
+    pub voter_token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut, seeds = [b"credit_escrow"], bump)]
+    pub credit_escrow: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct ConvertLegacyCredits<'info> {
+    #[account(has_one = credit_mint, seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
     #[account(mut)]
+    pub organization: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"organization", organization.key().as_ref()],
+        bump
+    )]
+    pub organization_info: Account<'info, OrganizationInfo>,
+    /// CHECK: PDA that signs for the credit mint and the escrow; it holds no data
+    #[account(seeds = [b"authority"], bump = config.authority_bump)]
+    pub program_authority: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub credit_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        init_if_needed,
+        payer = organization,
+        associated_token::mint = credit_mint,
+        associated_token::authority = organization,
+        associated_token::token_program = token_program
+    )]
+    pub organization_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetVoteWeighting<'info> {
+    #[account(mut, has_one = owner, seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetEligibilityThreshold<'info> {
+    #[account(mut, has_one = owner, seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetVotingThresholds<'info> {
+    #[account(mut, has_one = owner, seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(claim_id: u64)]
+pub struct FinalizeVoting<'info> {
+    #[account(mut, has_one = credit_mint, seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(mut, constraint = claim.id == claim_id, has_one = organization)]
+    pub claim: Account<'info, Claim>,
+    pub organization: SystemAccount<'info>,
+    #[account(
+        mut,
+        seeds = [b"organization", organization.key().as_ref()],
+        bump
+    )]
     pub organization_info: Account<'info, OrganizationInfo>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    /// CHECK: PDA that signs for the credit mint and the escrow; it holds no data
+    #[account(seeds = [b"authority"], bump = config.authority_bump)]
+    pub program_authority: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub credit_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = credit_mint,
+        associated_token::authority = organization,
+        associated_token::token_program = token_program
+    )]
+    pub organization_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
 pub struct CreateLendRequest<'info> {
+    #[account(has_one = credit_mint, seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
     #[account(mut)]
     pub lend_request_counter: Account<'info, Counter>,
     #[account(init, payer = borrower, space = 8 + LendRequest::LEN)]
@@ -349,79 +1089,105 @@ pub struct CreateLendRequest<'info> {
     pub lender: SystemAccount<'info>,
     pub system_program: Program<'info, System>,
     #[account(
-        mut,
         seeds = [b"organization", borrower.key().as_ref()],
         bump
     )]
     pub borrower_info: Account<'info, OrganizationInfo>,
+    pub credit_mint: InterfaceAccount<'info, Mint>,
     #[account(
-        mut,cargo-features = ["edition2024"]
-[package]
-name = "zero-knowledge-proofs"
-version = "0.1.0"
-edition = "2024"
-cargo-features = ["edition2024"]
-[package]
-name = "zero-knowledge-proofs"
-version = "0.1.0"
-edition = "2024"
-
-[lib]
-name = "zero_knowledge_proofs"
-path = "src/lib.rs"
-
-[dependencies]
-num-bigint = { version = "0.4.6", features = ["rand", "serde"] }
-num-traits = "0.2.19"
-rand = "0.8"
-num-integer = "0.1.46"
-sha2 = "0.10"
-serde = { version = "1.0.214", features = ["derive"] }
-serde_json = "1.0.132"
-bincode = "1.0.0"
-[lib]
-name = "zero_knowledge_proofs"
-path = "src/lib.rs"
-
-[dependencies]
-num-bigint = { version = "0.4.6", features = ["rand", "serde"] }
-num-traits = "0.2.19"
-rand = "0.8"
-num-integer = "0.1.46"
-sha2 = "0.10"
-serde = { version = "1.0.214", features = ["derive"] }
-serde_json = "1.0.132"
-bincode = "1.0.0"
-        seeds = [b"organization", lender.key().as_ref()],
-        bump
+        associated_token::mint = credit_mint,
+        associated_token::authority = borrower,
+        associated_token::token_program = token_program
     )]
-    pub lender_info: Account<'info, OrganizationInfo>,
+    pub borrower_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>,
 }
 
 #[derive(Accounts)]
 #[instruction(lend_request_id: u64)]
 pub struct LendTokens<'info> {
+    #[account(has_one = credit_mint, seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
     #[account(mut)]
     pub lend_request: Account<'info, LendRequest>,
     #[account(mut)]
     pub lender: Signer<'info>,
-    #[account(mut)]
+    #[account(address = lend_request.borrower)]
+    pub borrower: SystemAccount<'info>,
+    #[account(
+        mut,
+        seeds = [b"organization", borrower.key().as_ref()],
+        bump
+    )]
     pub borrower_info: Account<'info, OrganizationInfo>,
-    #[account(mut)]
-    pub lender_info: Account<'info, OrganizationInfo>,
+    pub credit_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::mint = credit_mint,
+        associated_token::authority = lender,
+        associated_token::token_program = token_program
+    )]
+    pub lender_token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        init_if_needed,
+        payer = lender,
+        associated_token::mint = credit_mint,
+        associated_token::authority = borrower,
+        associated_token::token_program = token_program
+    )]
+    pub borrower_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
 pub struct RepayTokens<'info> {
+    #[account(has_one = credit_mint, seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(mut, has_one = borrower)]
+    pub lend_request: Account<'info, LendRequest>,
     #[account(mut)]
     pub borrower: Signer<'info>,
-    #[account(mut)]
+    #[account(address = lend_request.lender)]
     pub lender: SystemAccount<'info>,
-    #[account(mut)]
+    #[account(
+        mut,
+        seeds = [b"organization", borrower.key().as_ref()],
+        bump
+    )]
     pub borrower_info: Account<'info, OrganizationInfo>,
-    /// CHECK: This is the organization's account key
+    pub credit_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::mint = credit_mint,
+        associated_token::authority = borrower,
+        associated_token::token_program = token_program
+    )]
+    pub borrower_token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        associated_token::mint = credit_mint,
+        associated_token::authority = lender,
+        associated_token::token_program = token_program
+    )]
+    pub lender_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct RegisterOrganization<'info> {
     #[account(mut)]
-    pub lender_info: Account<'info, OrganizationInfo>,
+    pub organization: Signer<'info>,
+    #[account(
+        init,
+        payer = organization,
+        space = 8 + OrganizationInfo::LEN,
+        seeds = [b"organization", organization.key().as_ref()],
+        bump
+    )]
+    pub organization_info: Account<'info, OrganizationInfo>,
+    pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
@@ -444,15 +1210,238 @@ pub struct AddOrganizationEmission<'info> {
     pub organization_info: Account<'info, OrganizationInfo>,
 }
 
+#[derive(Accounts)]
+pub struct RetireCredits<'info> {
+    #[account(mut, has_one = credit_mint, seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(mut)]
+    pub organization: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"organization", organization.key().as_ref()],
+        bump
+    )]
+    pub organization_info: Account<'info, OrganizationInfo>,
+    #[account(
+        init,
+        payer = organization,
+        space = 8 + RetirementCertificate::LEN,
+        seeds = [b"retirement", organization.key().as_ref(), &organization_info.retirement_count.to_le_bytes()],
+        bump
+    )]
+    pub certificate: Account<'info, RetirementCertificate>,
+    #[account(mut)]
+    pub credit_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::mint = credit_mint,
+        associated_token::authority = organization,
+        associated_token::token_program = token_program
+    )]
+    pub organization_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(order_id: u64)]
+pub struct PlaceOrder<'info> {
+    #[account(has_one = credit_mint, seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(mut)]
+    pub maker: Signer<'info>,
+    #[account(
+        init,
+        payer = maker,
+        space = 8 + Order::LEN,
+        seeds = [b"order", maker.key().as_ref(), &order_id.to_le_bytes()],
+        bump
+    )]
+    pub order: Account<'info, Order>,
+    /// CHECK: PDA that signs for the credit mint and the escrow; it holds no data
+    #[account(seeds = [b"authority"], bump = config.authority_bump)]
+    pub program_authority: UncheckedAccount<'info>,
+    pub credit_mint: InterfaceAccount<'info, Mint>,
+    /// Quote token the order is priced in; omitted for SOL.
+    pub quote_mint: Option<InterfaceAccount<'info, Mint>>,
+    /// Mint of the escrowed asset: the credit mint for sells, the quote mint for token-priced buys.
+    pub escrow_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        init,
+        payer = maker,
+        seeds = [b"order_escrow", order.key().as_ref()],
+        bump,
+        token::mint = escrow_mint,
+        token::authority = program_authority,
+        token::token_program = token_program
+    )]
+    pub escrow: Option<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut)]
+    pub maker_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(order_id: u64)]
+pub struct FillOrder<'info> {
+    #[account(has_one = credit_mint, seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(mut)]
+    pub taker: Signer<'info>,
+    #[account(mut, address = order.maker)]
+    pub maker: SystemAccount<'info>,
+    #[account(
+        mut,
+        seeds = [b"order", order.maker.as_ref(), &order_id.to_le_bytes()],
+        bump
+    )]
+    pub order: Account<'info, Order>,
+    /// CHECK: PDA that signs for the credit mint and the escrow; it holds no data
+    #[account(seeds = [b"authority"], bump = config.authority_bump)]
+    pub program_authority: UncheckedAccount<'info>,
+    pub credit_mint: InterfaceAccount<'info, Mint>,
+    pub quote_mint: Option<InterfaceAccount<'info, Mint>>,
+    #[account(mut, seeds = [b"order_escrow", order.key().as_ref()], bump)]
+    pub escrow: Option<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        mut,
+        token::mint = credit_mint,
+        token::authority = taker,
+        token::token_program = token_program
+    )]
+    pub taker_credit_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        init_if_needed,
+        payer = taker,
+        associated_token::mint = credit_mint,
+        associated_token::authority = maker,
+        associated_token::token_program = token_program
+    )]
+    pub maker_credit_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut)]
+    pub taker_quote_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut)]
+    pub maker_quote_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(order_id: u64)]
+pub struct CancelOrder<'info> {
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(mut)]
+    pub maker: Signer<'info>,
+    #[account(
+        mut,
+        close = maker,
+        seeds = [b"order", maker.key().as_ref(), &order_id.to_le_bytes()],
+        bump
+    )]
+    pub order: Account<'info, Order>,
+    /// CHECK: PDA that signs for the credit mint and the escrow; it holds no data
+    #[account(seeds = [b"authority"], bump = config.authority_bump)]
+    pub program_authority: UncheckedAccount<'info>,
+    pub escrow_mint: Option<InterfaceAccount<'info, Mint>>,
+    #[account(mut, seeds = [b"order_escrow", order.key().as_ref()], bump)]
+    pub escrow: Option<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut, token::authority = maker)]
+    pub maker_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
 #[account]
 pub struct Config {
     pub owner: Pubkey,
     pub voting_period: u64,
     pub total_carbon_credits: u64,
+    pub reputation_weight: u64,
+    pub stake_weight: u64,
+    pub quorum_base: u64,
+    pub quorum_per_credit_bps: u64,
+    pub min_voters: u64,
+    pub approval_bps: u64,
+    pub supermajority_bps: u64,
+    pub supermajority_claim_size: u64,
+    pub credit_mint: Pubkey,
+    pub authority_bump: u8,
+    /// Score borrowers must prove to reach before they can request a loan.
+    pub eligibility_threshold: u64,
+    /// Bump of the config PDA. There is one config per program, so every instruction can pin it.
+    pub bump: u8,
 }
 
+pub const BPS_DENOMINATOR: u64 = 10_000;
+
 impl Config {
-    pub const LEN: usize = 32 + 8 + 8;
+    pub const LEN: usize = 32 + 8 + 8 + 8 + 8 + 6 * 8 + 32 + 1 + 8 + 1;
+
+    /// A config with the default policy. Quorum starts at 100 weight and grows by 10% of the credits a
+    /// claim asks for, and at least three organizations must vote, so a lone voter cannot approve a
+    /// claim of any size.
+    pub fn new(owner: Pubkey, voting_period: u64, bump: u8) -> Self {
+        Config {
+            owner,
+            voting_period,
+            total_carbon_credits: 0,
+            reputation_weight: 1,
+            stake_weight: 1,
+            quorum_base: 100,
+            quorum_per_credit_bps: 1_000,
+            min_voters: 3,
+            approval_bps: 5_000,
+            supermajority_bps: 6_667,
+            supermajority_claim_size: 10_000,
+            credit_mint: Pubkey::default(),
+            authority_bump: 0,
+            eligibility_threshold: 0,
+            bump,
+        }
+    }
+
+    /// Weight of a single vote: the voter's reputation and locked stake, each scaled by the configured policy.
+    /// Fails with `MathOverflow` rather than wrapping when the weights are set high.
+    pub fn vote_weight(&self, reputation_score: u64, stake: u64) -> Result<u64> {
+        reputation_score
+            .checked_mul(self.reputation_weight)
+            .zip(stake.checked_mul(self.stake_weight))
+            .and_then(|(reputation, stake)| reputation.checked_add(stake))
+            .ok_or_else(|| ErrorCode::MathOverflow.into())
+    }
+
+    /// Vote weight a claim must attract before it can be decided; grows with the credits it asks for.
+    pub fn required_quorum(&self, demanded_tokens: u64) -> u128 {
+        self.quorum_base as u128 + demanded_tokens as u128 * self.quorum_per_credit_bps as u128 / BPS_DENOMINATOR as u128
+    }
+
+    /// Share of the cast weight, in basis points, that must vote yes; large claims need a supermajority.
+    pub fn required_approval_bps(&self, demanded_tokens: u64) -> u64 {
+        if demanded_tokens >= self.supermajority_claim_size {
+            self.supermajority_bps
+        } else {
+            self.approval_bps
+        }
+    }
+
+    /// Decide a claim whose voting period is over. Claims that miss quorum or turnout are inconclusive.
+    pub fn claim_outcome(&self, claim: &Claim) -> ClaimStatus {
+        let voters = claim.yes_votes.saturating_add(claim.no_votes);
+        let total_weight = claim.yes_weight as u128 + claim.no_weight as u128;
+
+        if voters < self.min_voters || total_weight == 0 || total_weight < self.required_quorum(claim.demanded_tokens) {
+            return ClaimStatus::Inconclusive;
+        }
+
+        let approval_bps = self.required_approval_bps(claim.demanded_tokens) as u128;
+        if claim.yes_weight as u128 * BPS_DENOMINATOR as u128 > total_weight * approval_bps {
+            ClaimStatus::Approved
+        } else {
+            ClaimStatus::Rejected
+        }
+    }
 }
 
 
@@ -464,7 +1453,7 @@ pub struct Counter {
 #[derive(Accounts)]
 pub struct GetConfig<'info> {
     /// CHECK: This is the organization's account key
-    #[account()]
+    #[account(seeds = [b"config"], bump = config.bump)]
     pub config: Account<'info, Config>,
 }
 
@@ -490,7 +1479,7 @@ pub struct GetOrganization<'info> {
 #[derive(Accounts)]
 pub struct GetTotalCarbonCredits<'info> {
     /// CHECK: This is the organization's account key
-    #[account()]
+    #[account(seeds = [b"config"], bump = config.bump)]
     pub config: Account<'info, Config>,
 }
 
@@ -500,6 +1489,16 @@ pub struct ConfigResponse {
     pub owner: Pubkey,
     pub voting_period: u64,
     pub total_carbon_credits: u64,
+    pub reputation_weight: u64,
+    pub stake_weight: u64,
+    pub quorum_base: u64,
+    pub quorum_per_credit_bps: u64,
+    pub min_voters: u64,
+    pub approval_bps: u64,
+    pub supermajority_bps: u64,
+    pub supermajority_claim_size: u64,
+    pub credit_mint: Pubkey,
+    pub eligibility_threshold: u64,
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize)]
@@ -516,6 +1515,8 @@ pub struct ClaimResponse {
     pub voting_end_time: u64,
     pub yes_votes: u64,
     pub no_votes: u64,
+    pub yes_weight: u64,
+    pub no_weight: u64,
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize)]
@@ -529,6 +1530,8 @@ pub struct OrganizationResponse {
     pub total_returned: u64,
     pub name: String,
     pub emissions: u64,
+    pub retired_credits: u64,
+    pub net_emissions: u64,
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize)]
@@ -543,7 +1546,7 @@ pub struct LendRequestResponse {
     pub lender: Pubkey,
     pub status: LentStatus,
     pub eligibility_score: u64,
-    pub proof_data: String,
+    pub proof_data: Vec<u8>,
     pub time: u64,
     pub amount: u64,
     pub role: String,
@@ -569,6 +1572,19 @@ pub enum ClaimStatus {
     Active,
     Approved,
     Rejected,
+    Inconclusive,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+pub enum OrderSide {
+    Buy,
+    Sell,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+pub enum OrderStatus {
+    Open,
+    Filled,
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
@@ -576,6 +1592,7 @@ pub enum LentStatus {
     Active,
     Approved,
     Rejected,
+    Repaid,
 }
 
 #[account]
@@ -592,6 +1609,10 @@ pub struct Claim {
     pub voting_end_time: u64,
     pub yes_votes: u64,
     pub no_votes: u64,
+    pub yes_weight: u64,
+    pub no_weight: u64,
+    /// Credits from this claim already cited by retirement certificates.
+    pub retired: u64,
 }
 
 impl Claim {
@@ -606,7 +1627,48 @@ impl Claim {
         1 +                     // status
         8 +                     // voting_end_time
         8 +                     // yes_votes
-        8;                      // no_votes
+        8 +                     // no_votes
+        8 +                     // yes_weight
+        8 +                     // no_weight
+        8;                      // retired
+
+    /// Credits from this claim that retirements can still cite.
+    pub fn retirable(&self) -> u64 {
+        self.demanded_tokens.saturating_sub(self.retired)
+    }
+
+    /// Reject a vote by `voter` at `now` unless the claim is still open, the voter is not the claiming
+    /// organization and `record` has not been used yet.
+    pub fn check_vote(&self, voter: &Pubkey, record: &VoteRecord, now: u64) -> Result<()> {
+        if self.status != ClaimStatus::Active || now > self.voting_end_time {
+            return Err(ErrorCode::VotingEnded.into());
+        }
+
+        if self.organization == *voter {
+            return Err(ErrorCode::SelfVote.into());
+        }
+
+        // The record is created on the first vote, so a non-default voter means a repeat.
+        if record.voter != Pubkey::default() {
+            return Err(ErrorCode::AlreadyVoted.into());
+        }
+
+        Ok(())
+    }
+
+    pub fn tally_vote(&mut self, vote: &VoteOption, weight: u64) -> Result<()> {
+        match vote {
+            VoteOption::Yes => {
+                self.yes_votes = checked_tally(self.yes_votes, 1)?;
+                self.yes_weight = checked_tally(self.yes_weight, weight)?;
+            }
+            VoteOption::No => {
+                self.no_votes = checked_tally(self.no_votes, 1)?;
+                self.no_weight = checked_tally(self.no_weight, weight)?;
+            }
+        }
+        Ok(())
+    }
 }
 
 #[account]
@@ -615,14 +1677,45 @@ pub struct LendRequest {
     pub borrower: Pubkey,
     pub lender: Pubkey,
     pub amount: u64,
+    /// The configured threshold the borrower's on-chain record was proven to reach.
     pub eligibility_score: u64,
-    pub proof_data: String,
+    pub proof_data: Vec<u8>,
     pub status: LentStatus,
     pub time: u64,
+    /// Commitment to the borrower's balances the proof was checked against, so `proof_data` can be
+    /// re-verified later.
+    pub record_commitment: [u8; 32],
+    /// Credits paid back on this loan so far.
+    pub repaid: u64,
 }
 
 impl LendRequest {
-    pub const LEN: usize = 8 + 32 + 32 + 8 + 8 + 50 + 1 + 8;
+    pub const LEN: usize = 8 + 32 + 32 + 8 + 8 + 4 + verifier::PROOF_LEN + 1 + 8 + 32 + 8;
+
+    /// Credits still owed on this loan.
+    pub fn outstanding(&self) -> u64 {
+        self.amount.saturating_sub(self.repaid)
+    }
+
+    /// Count `amount` against this loan, marking it `Repaid` once nothing is owed. Only lent loans can
+    /// be repaid, and never by more than is outstanding.
+    pub fn record_repayment(&mut self, amount: u64) -> Result<()> {
+        if self.status != LentStatus::Approved {
+            return Err(ErrorCode::RequestNotActive.into());
+        }
+        if amount == 0 {
+            return Err(ErrorCode::InvalidAmount.into());
+        }
+        if amount > self.outstanding() {
+            return Err(ErrorCode::RepaymentExceedsLoan.into());
+        }
+
+        self.repaid += amount;
+        if self.repaid == self.amount {
+            self.status = LentStatus::Repaid;
+        }
+        Ok(())
+    }
 }
 
 #[account]
@@ -635,10 +1728,124 @@ pub struct OrganizationInfo {
     pub total_returned: u64,
     pub name: String,
     pub emissions: u64,
+    pub retired_credits: u64,
+    pub retirement_count: u64,
 }
 
 impl OrganizationInfo {
-    pub const LEN: usize = 8 + 8 + 8 + 4 + 8 + 8 + 50 + 8;
+    pub const LEN: usize = 8 + 8 + 8 + 4 + 8 + 8 + 50 + 8 + 8 + 8;
+
+    /// Reputation an organization gains when one of its claims is approved, and loses when one is rejected.
+    pub const CLAIM_REPUTATION: u64 = 10;
+    /// Reputation a borrower gains for each loan repaid in full.
+    pub const REPAID_LOAN_REPUTATION: u64 = 5;
+
+    /// Adjust reputation for a decided claim of this organization. Inconclusive claims leave it alone.
+    pub fn record_claim_outcome(&mut self, outcome: &ClaimStatus) {
+        match outcome {
+            ClaimStatus::Approved => self.reputation_score = self.reputation_score.saturating_add(Self::CLAIM_REPUTATION),
+            ClaimStatus::Rejected => self.reputation_score = self.reputation_score.saturating_sub(Self::CLAIM_REPUTATION),
+            ClaimStatus::Active | ClaimStatus::Inconclusive => {}
+        }
+    }
+
+    pub fn record_repaid_loan(&mut self) {
+        self.reputation_score = self.reputation_score.saturating_add(Self::REPAID_LOAN_REPUTATION);
+    }
+
+    /// Recorded emissions that have not been offset by retired credits.
+    pub fn net_emissions(&self) -> u64 {
+        self.emissions.saturating_sub(self.retired_credits)
+    }
+}
+
+/// A resting order on the credit book. `price` is in quote units (lamports or quote token base units)
+/// per credit, and `remaining` counts the credits still available to fill.
+#[account]
+pub struct Order {
+    pub id: u64,
+    pub maker: Pubkey,
+    pub side: OrderSide,
+    pub quote_mint: Option<Pubkey>,
+    pub price: u64,
+    pub amount: u64,
+    pub remaining: u64,
+    pub created_at: u64,
+    pub expires_at: u64,
+    pub status: OrderStatus,
+}
+
+impl Order {
+    /// Mint of the tokens an order escrows: credits for sells, the quote token for token-priced buys.
+    /// `None` for SOL-priced buys, whose escrow is lamports on the order account.
+    pub fn escrow_mint_for(side: &OrderSide, credit_mint: Pubkey, quote_mint: Option<Pubkey>) -> Option<Pubkey> {
+        match side {
+            OrderSide::Sell => Some(credit_mint),
+            OrderSide::Buy => quote_mint,
+        }
+    }
+
+    pub fn is_expired(&self, now: u64) -> bool {
+        now >= self.expires_at
+    }
+
+    /// Take `amount` credits off the order at `now` and return their cost in the quote. A partial fill
+    /// leaves the rest open; the fill that takes the last credit marks the order `Filled`.
+    pub fn fill(&mut self, amount: u64, now: u64) -> Result<u64> {
+        if self.status != OrderStatus::Open {
+            return Err(ErrorCode::OrderNotOpen.into());
+        }
+        if self.is_expired(now) {
+            return Err(ErrorCode::OrderExpired.into());
+        }
+        if amount == 0 || amount > self.remaining {
+            return Err(ErrorCode::InvalidAmount.into());
+        }
+
+        let cost = order_cost(self.price, amount)?;
+        self.remaining -= amount;
+        if self.remaining == 0 {
+            self.status = OrderStatus::Filled;
+        }
+        Ok(cost)
+    }
+
+    pub const LEN: usize = 8 +  // id
+        32 +                    // maker
+        1 +                     // side
+        1 + 32 +                // quote_mint
+        8 +                     // price
+        8 +                     // amount
+        8 +                     // remaining
+        8 +                     // created_at
+        8 +                     // expires_at
+        1;                      // status
+}
+
+/// Proof that credits were burned on behalf of a beneficiary. One account per retirement, keyed by the
+/// retiring organization and its running retirement count.
+#[account]
+pub struct RetirementCertificate {
+    pub id: u64,
+    pub organization: Pubkey,
+    pub beneficiary: Pubkey,
+    pub amount: u64,
+    pub claim_ids: Vec<u64>,
+    pub timestamp: u64,
+    pub reason: Option<String>,
+}
+
+impl RetirementCertificate {
+    pub const MAX_CLAIMS: usize = 10;
+    pub const MAX_REASON_LEN: usize = 100;
+
+    pub const LEN: usize = 8 +                  // id
+        32 +                                    // organization
+        32 +                                    // beneficiary
+        8 +                                     // amount
+        4 + (Self::MAX_CLAIMS * 8) +            // claim_ids
+        8 +                                     // timestamp
+        1 + 4 + Self::MAX_REASON_LEN;           // reason
 }
 
 #[derive(Accounts)]
@@ -660,10 +1867,12 @@ pub struct VoteRecord {
     pub voter: Pubkey,
     pub vote: VoteOption,
     pub timestamp: u64,
+    pub weight: u64,
+    pub stake: u64,
 }
 
 impl VoteRecord {
-    pub const LEN: usize = 32 + 1 + 8;
+    pub const LEN: usize = 32 + 1 + 8 + 8 + 8;
 }
 
 #[error_code]
@@ -684,4 +1893,55 @@ pub enum ErrorCode {
     NotEnoughCredits,
     #[msg("Borrower not eligible")]
     BorrowerNotEligible,
+    #[msg("Organizations cannot vote on their own claims")]
+    SelfVote,
+    #[msg("Voter has no voting weight")]
+    NoVotingWeight,
+    #[msg("At least one vote weight must be non-zero")]
+    InvalidVoteWeighting,
+    #[msg("Approval thresholds must be at most 10000 bps and the supermajority at least the approval threshold")]
+    InvalidVotingThresholds,
+    #[msg("Amount must be greater than zero")]
+    InvalidAmount,
+    #[msg("Retirement reason is too long")]
+    ReasonTooLong,
+    #[msg("Too many originating claims")]
+    TooManyClaims,
+    #[msg("Claim has not been approved")]
+    ClaimNotApproved,
+    #[msg("Claim belongs to another organization")]
+    ClaimNotOwned,
+    #[msg("Claim is listed more than once")]
+    DuplicateClaim,
+    #[msg("Cited claims do not have enough unretired credits")]
+    ClaimCreditsExhausted,
+    #[msg("Order is not open")]
+    OrderNotOpen,
+    #[msg("Order has expired")]
+    OrderExpired,
+    #[msg("Accounts do not match the order")]
+    InvalidOrderAccounts,
+    #[msg("Arithmetic overflow")]
+    MathOverflow,
+    #[msg("Repayment exceeds what is owed on the loan")]
+    RepaymentExceedsLoan,
+    #[msg("Wrong number of public inputs for the proof")]
+    ProofPublicInputCount,
+    #[msg("Public input is not a canonical scalar field element")]
+    NonCanonicalPublicInput,
+    #[msg("Proof contains an invalid curve point")]
+    MalformedProof,
+    #[msg("No eligibility verifying key is configured")]
+    EligibilityKeyUnavailable,
+}
+
+impl From<verifier::VerifierError> for ErrorCode {
+    fn from(error: verifier::VerifierError) -> Self {
+        match error {
+            verifier::VerifierError::PublicInputCount => ErrorCode::ProofPublicInputCount,
+            verifier::VerifierError::NonCanonicalPublicInput => ErrorCode::NonCanonicalPublicInput,
+            verifier::VerifierError::MalformedProof => ErrorCode::MalformedProof,
+            verifier::VerifierError::InvalidProof => ErrorCode::BorrowerNotEligible,
+        }
+    }
 }
\ No newline at end of file
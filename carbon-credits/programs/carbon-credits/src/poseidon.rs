@@ -0,0 +1,52 @@
+//! circomlib-compatible Poseidon over BN254, so the program can recompute commitments that
+//! `zero_knowledge_proofs` builds into its circuits. On-chain this is the `sol_poseidon` syscall;
+//! off-chain builds use light-poseidon, the implementation behind that syscall.
+
+/// `sol_poseidon` parameter set: BN254 with `x^5` S-boxes, as in circomlib.
+#[cfg(target_os = "solana")]
+const BN254_X5: u64 = 0;
+/// `sol_poseidon` endianness of inputs and output.
+#[cfg(target_os = "solana")]
+const BIG_ENDIAN: u64 = 0;
+
+#[cfg(target_os = "solana")]
+extern "C" {
+    fn sol_poseidon(parameters: u64, endianness: u64, vals: *const u8, val_len: u64, hash_result: *mut u8) -> u64;
+}
+
+/// Poseidon of 1 to 12 big-endian field elements. `None` if an input is not below the modulus.
+#[cfg(target_os = "solana")]
+pub fn hash(inputs: &[&[u8]]) -> Option<[u8; 32]> {
+    let mut result = [0u8; 32];
+    // SAFETY: the syscall reads `inputs.len()` slice descriptors and writes 32 bytes to `result`.
+    let status = unsafe {
+        sol_poseidon(BN254_X5, BIG_ENDIAN, inputs.as_ptr() as *const u8, inputs.len() as u64, result.as_mut_ptr())
+    };
+    (status == 0).then_some(result)
+}
+
+/// Poseidon of 1 to 12 big-endian field elements. `None` if an input is not below the modulus.
+#[cfg(not(target_os = "solana"))]
+pub fn hash(inputs: &[&[u8]]) -> Option<[u8; 32]> {
+    use light_poseidon::{Poseidon, PoseidonBytesHasher};
+
+    Poseidon::<ark_bn254::Fr>::new_circom(inputs.len()).ok()?.hash_bytes_be(inputs).ok()
+}
+
+/// Commitment to a public key: Poseidon of its two 128-bit halves, matching
+/// `zero_knowledge_proofs::borrower_commitment`.
+pub fn key_commitment(key: &[u8; 32]) -> Option<[u8; 32]> {
+    let mut high = [0u8; 32];
+    let mut low = [0u8; 32];
+    high[16..].copy_from_slice(&key[..16]);
+    low[16..].copy_from_slice(&key[16..]);
+    hash(&[&high, &low])
+}
+
+/// Commitment to the balances a borrower's eligibility score is computed from, matching
+/// `zero_knowledge_proofs::record_commitment`. `carbon_credits` is the borrower's credit token balance.
+pub fn record_commitment(total_returned: u64, carbon_credits: u64, reputation: u64) -> Option<[u8; 32]> {
+    let [total_returned, carbon_credits, reputation] =
+        [total_returned, carbon_credits, reputation].map(crate::verifier::u64_to_public_input);
+    hash(&[&total_returned, &carbon_credits, &reputation])
+}
@@ -0,0 +1,114 @@
+//! Groth16 verification over BN254 using the alt_bn128 syscalls, so proofs generated off-chain by
+//! `zero_knowledge_proofs` can be checked inside the program.
+
+use solana_bn254::prelude::{alt_bn128_addition, alt_bn128_multiplication, alt_bn128_pairing};
+
+/// `a || b || c`, with G1 points as big-endian `x || y` and G2 points in EIP-197 order.
+pub const PROOF_LEN: usize = 64 + 128 + 64;
+
+/// Base field modulus, used to negate `a`.
+const BASE_FIELD_MODULUS: [u8; 32] = [
+    0x30, 0x64, 0x4e, 0x72, 0xe1, 0x31, 0xa0, 0x29, 0xb8, 0x50, 0x45, 0xb6, 0x81, 0x81, 0x58, 0x5d,
+    0x97, 0x81, 0x6a, 0x91, 0x68, 0x71, 0xca, 0x8d, 0x3c, 0x20, 0x8c, 0x16, 0xd8, 0x7c, 0xfd, 0x47,
+];
+
+/// Scalar field modulus. Public inputs must be reduced below it.
+const SCALAR_FIELD_MODULUS: [u8; 32] = [
+    0x30, 0x64, 0x4e, 0x72, 0xe1, 0x31, 0xa0, 0x29, 0xb8, 0x50, 0x45, 0xb6, 0x81, 0x81, 0x58, 0x5d,
+    0x28, 0x33, 0xe8, 0x48, 0x79, 0xb9, 0x70, 0x91, 0x43, 0xe1, 0xf5, 0x93, 0xf0, 0x00, 0x00, 0x01,
+];
+
+/// Why a proof was rejected. The program maps these onto its `ErrorCode`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VerifierError {
+    /// The number of public inputs does not match the verifying key.
+    PublicInputCount,
+    /// A public input is not reduced below the scalar field modulus.
+    NonCanonicalPublicInput,
+    /// A proof point is not a valid curve point.
+    MalformedProof,
+    /// The pairing check failed: the proof does not attest to these public inputs.
+    InvalidProof,
+}
+
+pub struct Groth16VerifyingKey {
+    pub alpha_g1: [u8; 64],
+    pub beta_g2: [u8; 128],
+    pub gamma_g2: [u8; 128],
+    pub delta_g2: [u8; 128],
+    /// One point for the constant term, then one per public input.
+    pub ic: &'static [[u8; 64]],
+}
+
+/// Check `e(-a, b) * e(alpha, beta) * e(vk_x, gamma) * e(c, delta) == 1`, where `vk_x` folds the
+/// public inputs into the key.
+pub fn verify(
+    vk: &Groth16VerifyingKey,
+    proof: &[u8; PROOF_LEN],
+    public_inputs: &[[u8; 32]],
+) -> Result<(), VerifierError> {
+    if public_inputs.len() + 1 != vk.ic.len() {
+        return Err(VerifierError::PublicInputCount);
+    }
+
+    let mut vk_x = vk.ic[0];
+    for (input, point) in public_inputs.iter().zip(&vk.ic[1..]) {
+        if *input >= SCALAR_FIELD_MODULUS {
+            return Err(VerifierError::NonCanonicalPublicInput);
+        }
+        // The key's points are valid, so these only fail on a broken syscall.
+        let term = alt_bn128_multiplication(&[point.as_slice(), input].concat())
+            .map_err(|_| VerifierError::InvalidProof)?;
+        let sum = alt_bn128_addition(&[vk_x.as_slice(), &term].concat()).map_err(|_| VerifierError::InvalidProof)?;
+        vk_x.copy_from_slice(&sum);
+    }
+
+    let neg_a = negate_g1(&proof[..64]).ok_or(VerifierError::MalformedProof)?;
+    let pairing_input = [
+        neg_a.as_slice(),
+        &proof[64..192],
+        &vk.alpha_g1,
+        &vk.beta_g2,
+        &vk_x,
+        &vk.gamma_g2,
+        &proof[192..],
+        &vk.delta_g2,
+    ]
+    .concat();
+
+    // The syscall rejects points that are off the curve or outside the subgroup.
+    let result = alt_bn128_pairing(&pairing_input).map_err(|_| VerifierError::MalformedProof)?;
+    if result.len() == 32 && result[..31].iter().all(|byte| *byte == 0) && result[31] == 1 {
+        Ok(())
+    } else {
+        Err(VerifierError::InvalidProof)
+    }
+}
+
+/// An integer as a 32-byte big-endian public input.
+pub fn u64_to_public_input(value: u64) -> [u8; 32] {
+    let mut input = [0u8; 32];
+    input[24..].copy_from_slice(&value.to_be_bytes());
+    input
+}
+
+/// `(x, p - y)`. The point at infinity is its own negation; `None` if `y` is not a field element.
+pub fn negate_g1(point: &[u8]) -> Option<[u8; 64]> {
+    let mut negated = [0u8; 64];
+    if point.iter().all(|byte| *byte == 0) {
+        return Some(negated);
+    }
+    let y: [u8; 32] = point[32..].try_into().ok()?;
+    if y >= BASE_FIELD_MODULUS {
+        return None;
+    }
+
+    negated[..32].copy_from_slice(&point[..32]);
+    let mut borrow = 0u16;
+    for i in (0..32).rev() {
+        let difference = BASE_FIELD_MODULUS[i] as u16 + 256 - y[i] as u16 - borrow;
+        negated[32 + i] = difference as u8;
+        borrow = if difference < 256 { 1 } else { 0 };
+    }
+    Some(negated)
+}
@@ -0,0 +1,71 @@
+use anchor_lang::prelude::Pubkey;
+use carbon_credits::{Claim, ClaimStatus, Config};
+
+fn config() -> Config {
+    Config::new(Pubkey::new_unique(), 86_400, 255)
+}
+
+/// A decided claim for `demanded_tokens` with the given turnout and weights.
+fn claim(demanded_tokens: u64, yes: (u64, u64), no: (u64, u64)) -> Claim {
+    Claim {
+        id: 0,
+        organization: Pubkey::new_unique(),
+        longitudes: Vec::new(),
+        latitudes: Vec::new(),
+        time_started: 0,
+        time_ended: 0,
+        demanded_tokens,
+        ipfs_hashes: Vec::new(),
+        status: ClaimStatus::Active,
+        voting_end_time: 0,
+        yes_votes: yes.0,
+        no_votes: no.0,
+        yes_weight: yes.1,
+        no_weight: no.1,
+        retired: 0,
+    }
+}
+
+#[test]
+fn vote_weight_scales_reputation_and_stake() {
+    let mut config = config();
+    config.reputation_weight = 2;
+    config.stake_weight = 3;
+    assert_eq!(config.vote_weight(10, 5).unwrap(), 35);
+    assert_eq!(config.vote_weight(0, 0).unwrap(), 0);
+    assert!(config.vote_weight(u64::MAX, 0).is_err());
+    assert!(config.vote_weight(u64::MAX / 2, u64::MAX / 3).is_err());
+}
+
+#[test]
+fn default_quorum_grows_with_the_claim() {
+    let config = config();
+    assert_eq!(config.required_quorum(0), 100);
+    assert_eq!(config.required_quorum(1_000), 200);
+    assert_eq!(config.required_quorum(u64::MAX), 100 + u64::MAX as u128 / 10);
+    assert!(config.min_voters > 1);
+}
+
+#[test]
+fn a_lone_vote_does_not_approve_a_claim() {
+    let config = config();
+    for demanded_tokens in [1, 1_000, u64::MAX] {
+        assert!(config.claim_outcome(&claim(demanded_tokens, (1, 1), (0, 0))) == ClaimStatus::Inconclusive);
+        assert!(config.claim_outcome(&claim(demanded_tokens, (1, u64::MAX), (0, 0))) == ClaimStatus::Inconclusive);
+    }
+    assert!(config.claim_outcome(&claim(0, (0, 0), (0, 0))) == ClaimStatus::Inconclusive);
+}
+
+#[test]
+fn claims_need_quorum_turnout_and_the_scaled_approval_share() {
+    let config = config();
+    // 1,000 credits need 200 weight from three voters and a simple majority.
+    assert!(config.claim_outcome(&claim(1_000, (2, 120), (1, 79))) == ClaimStatus::Inconclusive);
+    assert!(config.claim_outcome(&claim(1_000, (2, 120), (1, 80))) == ClaimStatus::Approved);
+    assert!(config.claim_outcome(&claim(1_000, (2, 100), (1, 100))) == ClaimStatus::Rejected);
+    assert!(config.claim_outcome(&claim(1_000, (1, 300), (1, 0))) == ClaimStatus::Inconclusive);
+
+    // From 10,000 credits on, 60% is not enough.
+    assert!(config.claim_outcome(&claim(10_000, (3, 1_200), (2, 800))) == ClaimStatus::Rejected);
+    assert!(config.claim_outcome(&claim(10_000, (3, 1_400), (2, 600))) == ClaimStatus::Approved);
+}
@@ -0,0 +1,50 @@
+use anchor_lang::prelude::Pubkey;
+use carbon_credits::{LendRequest, LentStatus};
+
+fn loan(amount: u64, status: LentStatus) -> LendRequest {
+    LendRequest {
+        id: 0,
+        borrower: Pubkey::new_unique(),
+        lender: Pubkey::new_unique(),
+        amount,
+        eligibility_score: 0,
+        proof_data: Vec::new(),
+        status,
+        time: 0,
+        record_commitment: [0; 32],
+        repaid: 0,
+    }
+}
+
+#[test]
+fn repayments_settle_the_loan_they_are_made_against() {
+    let mut loan = loan(100, LentStatus::Approved);
+    loan.record_repayment(40).unwrap();
+    assert_eq!(loan.outstanding(), 60);
+    assert!(loan.status == LentStatus::Approved);
+
+    loan.record_repayment(60).unwrap();
+    assert_eq!((loan.repaid, loan.outstanding()), (100, 0));
+    assert!(loan.status == LentStatus::Repaid);
+    // A settled loan cannot absorb repayments meant for another.
+    assert!(loan.record_repayment(1).is_err());
+    assert_eq!(loan.repaid, 100);
+}
+
+#[test]
+fn repayments_are_capped_at_what_is_owed() {
+    let mut loan = loan(100, LentStatus::Approved);
+    loan.record_repayment(30).unwrap();
+    assert!(loan.record_repayment(71).is_err());
+    assert!(loan.record_repayment(0).is_err());
+    assert_eq!(loan.repaid, 30);
+}
+
+#[test]
+fn only_lent_loans_can_be_repaid() {
+    for status in [LentStatus::Active, LentStatus::Rejected] {
+        let mut loan = loan(100, status);
+        assert!(loan.record_repayment(10).is_err());
+        assert_eq!(loan.repaid, 0);
+    }
+}
@@ -0,0 +1,63 @@
+use anchor_lang::prelude::Pubkey;
+use carbon_credits::{Order, OrderSide, OrderStatus};
+
+fn order(price: u64, amount: u64, expires_at: u64) -> Order {
+    Order {
+        id: 0,
+        maker: Pubkey::new_unique(),
+        side: OrderSide::Sell,
+        quote_mint: None,
+        price,
+        amount,
+        remaining: amount,
+        created_at: 0,
+        expires_at,
+        status: OrderStatus::Open,
+    }
+}
+
+#[test]
+fn partial_fills_leave_the_rest_open() {
+    let mut order = order(3, 10, 100);
+    assert_eq!(order.fill(4, 10).unwrap(), 12);
+    assert_eq!(order.remaining, 6);
+    assert!(order.status == OrderStatus::Open);
+
+    assert_eq!(order.fill(6, 20).unwrap(), 18);
+    assert_eq!(order.remaining, 0);
+    assert!(order.status == OrderStatus::Filled);
+    assert!(order.fill(1, 30).is_err());
+}
+
+#[test]
+fn fills_are_capped_at_what_remains() {
+    let mut order = order(3, 10, 100);
+    assert!(order.fill(11, 10).is_err());
+    assert!(order.fill(0, 10).is_err());
+    assert_eq!(order.remaining, 10);
+}
+
+#[test]
+fn expired_orders_cannot_be_filled() {
+    let mut order = order(3, 10, 100);
+    assert!(!order.is_expired(99));
+    assert!(order.is_expired(100));
+    assert!(order.fill(1, 100).is_err());
+    assert_eq!(order.remaining, 10);
+}
+
+#[test]
+fn fills_whose_cost_overflows_are_rejected() {
+    let mut order = order(u64::MAX, 2, 100);
+    assert!(order.fill(2, 10).is_err());
+    assert_eq!(order.remaining, 2);
+    assert!(order.status == OrderStatus::Open);
+}
+
+#[test]
+fn orders_escrow_credits_for_sells_and_the_quote_for_buys() {
+    let (credit_mint, quote_mint) = (Pubkey::new_unique(), Pubkey::new_unique());
+    assert_eq!(Order::escrow_mint_for(&OrderSide::Sell, credit_mint, Some(quote_mint)), Some(credit_mint));
+    assert_eq!(Order::escrow_mint_for(&OrderSide::Buy, credit_mint, Some(quote_mint)), Some(quote_mint));
+    assert_eq!(Order::escrow_mint_for(&OrderSide::Buy, credit_mint, None), None);
+}
@@ -0,0 +1,43 @@
+use carbon_credits::{ClaimStatus, OrganizationInfo};
+
+fn organization(reputation_score: u64) -> OrganizationInfo {
+    OrganizationInfo {
+        reputation_score,
+        carbon_credits: 0,
+        debt: 0,
+        times_borrowed: 0,
+        total_borrowed: 0,
+        total_returned: 0,
+        name: String::new(),
+        emissions: 0,
+        retired_credits: 0,
+        retirement_count: 0,
+    }
+}
+
+#[test]
+fn decided_claims_move_reputation() {
+    let mut org = organization(0);
+    org.record_claim_outcome(&ClaimStatus::Approved);
+    org.record_claim_outcome(&ClaimStatus::Approved);
+    assert_eq!(org.reputation_score, 2 * OrganizationInfo::CLAIM_REPUTATION);
+
+    org.record_claim_outcome(&ClaimStatus::Inconclusive);
+    assert_eq!(org.reputation_score, 2 * OrganizationInfo::CLAIM_REPUTATION);
+
+    org.record_claim_outcome(&ClaimStatus::Rejected);
+    org.record_claim_outcome(&ClaimStatus::Rejected);
+    org.record_claim_outcome(&ClaimStatus::Rejected);
+    assert_eq!(org.reputation_score, 0);
+
+    let mut org = organization(u64::MAX);
+    org.record_claim_outcome(&ClaimStatus::Approved);
+    assert_eq!(org.reputation_score, u64::MAX);
+}
+
+#[test]
+fn repaid_loans_build_reputation() {
+    let mut org = organization(1);
+    org.record_repaid_loan();
+    assert_eq!(org.reputation_score, 1 + OrganizationInfo::REPAID_LOAN_REPUTATION);
+}
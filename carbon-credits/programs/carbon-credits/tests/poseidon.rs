@@ -0,0 +1,15 @@
+use carbon_credits::poseidon;
+use zero_knowledge_proofs::{borrower_commitment, record_commitment};
+
+#[test]
+fn commitments_match_the_proving_crate() {
+    for key in [[0u8; 32], [7; 32], [0xff; 32], core::array::from_fn(|i| i as u8)] {
+        assert_eq!(poseidon::key_commitment(&key), Some(borrower_commitment(&key).to_bytes_be()));
+    }
+    for (total_returned, carbon_credits, reputation) in [(0, 0, 0), (50, 40, 90), (u64::MAX, 1, u64::MAX)] {
+        assert_eq!(
+            poseidon::record_commitment(total_returned, carbon_credits, reputation),
+            Some(record_commitment(total_returned, carbon_credits, reputation).to_bytes_be()),
+        );
+    }
+}
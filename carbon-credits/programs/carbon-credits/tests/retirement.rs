@@ -0,0 +1,42 @@
+use anchor_lang::prelude::Pubkey;
+use carbon_credits::{draw_retirement, Claim, ClaimStatus};
+
+fn claim(demanded_tokens: u64, retired: u64) -> Claim {
+    Claim {
+        id: 0,
+        organization: Pubkey::new_unique(),
+        longitudes: Vec::new(),
+        latitudes: Vec::new(),
+        time_started: 0,
+        time_ended: 0,
+        demanded_tokens,
+        ipfs_hashes: Vec::new(),
+        status: ClaimStatus::Approved,
+        voting_end_time: 0,
+        yes_votes: 0,
+        no_votes: 0,
+        yes_weight: 0,
+        no_weight: 0,
+        retired,
+    }
+}
+
+#[test]
+fn retirements_draw_claims_in_order() {
+    let mut claims = [claim(100, 40), claim(50, 0)];
+    draw_retirement(&mut claims, 80).unwrap();
+    assert_eq!([claims[0].retired, claims[1].retired], [100, 20]);
+    assert_eq!([claims[0].retirable(), claims[1].retirable()], [0, 30]);
+}
+
+#[test]
+fn a_claim_backs_at_most_its_own_credits() {
+    let mut claims = [claim(100, 0)];
+    draw_retirement(&mut claims, 100).unwrap();
+    assert!(draw_retirement(&mut claims, 1).is_err());
+
+    let mut claims = [claim(100, 30), claim(10, 5)];
+    assert!(draw_retirement(&mut claims, 76).is_err());
+    assert_eq!([claims[0].retired, claims[1].retired], [30, 5]);
+    assert!(draw_retirement(&mut [], 1).is_err());
+}
@@ -0,0 +1,60 @@
+use ark_bn254::{Fr, G1Affine};
+use ark_ec::{AffineRepr, CurveGroup};
+use carbon_credits::verifier::{self, Groth16VerifyingKey, VerifierError, PROOF_LEN};
+use carbon_credits::ErrorCode;
+use rand::rngs::OsRng;
+use zero_knowledge_proofs::groth16::{self, g1_to_be_bytes};
+use zero_knowledge_proofs::{eligibility_circuit, eligibility_proof, eligibility_public_inputs};
+
+const BORROWER: [u8; 32] = [7; 32];
+
+/// Fresh keys for the eligibility circuit in the layout the program embeds, and a proof that
+/// `90 * 40 + 50 = 3650` reaches 3000.
+fn key_and_proof() -> (Groth16VerifyingKey, [u8; PROOF_LEN]) {
+    let r1cs = eligibility_circuit(0, 0, 0, 0, &[0; 32]).unwrap().to_r1cs().unwrap();
+    let (pk, vk) = groth16::setup(&r1cs, &mut OsRng).unwrap();
+    let vk = groth16::verifying_key_to_solana(&vk);
+    let vk = Groth16VerifyingKey {
+        alpha_g1: vk.alpha_g1,
+        beta_g2: vk.beta_g2,
+        gamma_g2: vk.gamma_g2,
+        delta_g2: vk.delta_g2,
+        ic: vk.ic.leak(),
+    };
+    let proof = eligibility_proof(&pk, &BORROWER, 3_000, 50, 40, 90).unwrap();
+    (vk, proof.try_into().unwrap())
+}
+
+#[test]
+fn accepts_eligibility_proofs_and_rejects_tampered_inputs() {
+    let (vk, proof) = key_and_proof();
+    let inputs = eligibility_public_inputs(3_000, &BORROWER, 50, 40, 90);
+    assert_eq!(verifier::verify(&vk, &proof, &inputs), Ok(()));
+
+    for tampered in [
+        eligibility_public_inputs(3_001, &BORROWER, 50, 40, 90),
+        eligibility_public_inputs(3_000, &[8; 32], 50, 40, 90),
+        eligibility_public_inputs(3_000, &BORROWER, 50, 41, 90),
+    ] {
+        assert_eq!(verifier::verify(&vk, &proof, &tampered), Err(VerifierError::InvalidProof));
+    }
+    assert!(matches!(ErrorCode::from(VerifierError::InvalidProof), ErrorCode::BorrowerNotEligible));
+
+    assert_eq!(verifier::verify(&vk, &proof, &inputs[..2]), Err(VerifierError::PublicInputCount));
+    let mut unreduced = inputs;
+    unreduced[0] = [0xff; 32];
+    assert_eq!(verifier::verify(&vk, &proof, &unreduced), Err(VerifierError::NonCanonicalPublicInput));
+    let mut off_curve = proof;
+    off_curve[63] ^= 1;
+    assert_eq!(verifier::verify(&vk, &off_curve, &inputs), Err(VerifierError::MalformedProof));
+}
+
+#[test]
+fn negate_g1_matches_arkworks() {
+    for scalar in [1u64, 2, 7, 1 << 40] {
+        let point = (G1Affine::generator() * Fr::from(scalar)).into_affine();
+        assert_eq!(verifier::negate_g1(&g1_to_be_bytes(&point)), Some(g1_to_be_bytes(&-point)));
+    }
+    assert_eq!(verifier::negate_g1(&[0; 64]), Some([0; 64]));
+    assert_eq!(verifier::negate_g1(&[0xff; 64]), None);
+}
@@ -0,0 +1,69 @@
+use anchor_lang::prelude::Pubkey;
+use carbon_credits::{Claim, ClaimStatus, VoteOption, VoteRecord};
+
+fn claim(organization: Pubkey, voting_end_time: u64) -> Claim {
+    Claim {
+        id: 0,
+        organization,
+        longitudes: Vec::new(),
+        latitudes: Vec::new(),
+        time_started: 0,
+        time_ended: 0,
+        demanded_tokens: 100,
+        ipfs_hashes: Vec::new(),
+        status: ClaimStatus::Active,
+        voting_end_time,
+        yes_votes: 0,
+        no_votes: 0,
+        yes_weight: 0,
+        no_weight: 0,
+        retired: 0,
+    }
+}
+
+fn record(voter: Pubkey) -> VoteRecord {
+    VoteRecord { voter, vote: VoteOption::Yes, timestamp: 0, weight: 0, stake: 0 }
+}
+
+#[test]
+fn votes_are_accepted_until_voting_ends() {
+    let claim = claim(Pubkey::new_unique(), 100);
+    let voter = Pubkey::new_unique();
+    let unused = record(Pubkey::default());
+    assert!(claim.check_vote(&voter, &unused, 100).is_ok());
+    assert!(claim.check_vote(&voter, &unused, 101).is_err());
+}
+
+#[test]
+fn decided_claims_take_no_votes() {
+    let mut claim = claim(Pubkey::new_unique(), 100);
+    claim.status = ClaimStatus::Approved;
+    assert!(claim.check_vote(&Pubkey::new_unique(), &record(Pubkey::default()), 10).is_err());
+}
+
+#[test]
+fn organizations_cannot_vote_on_their_own_claims() {
+    let organization = Pubkey::new_unique();
+    let claim = claim(organization, 100);
+    assert!(claim.check_vote(&organization, &record(Pubkey::default()), 10).is_err());
+}
+
+#[test]
+fn each_voter_votes_once() {
+    let claim = claim(Pubkey::new_unique(), 100);
+    let voter = Pubkey::new_unique();
+    assert!(claim.check_vote(&voter, &record(voter), 10).is_err());
+}
+
+#[test]
+fn votes_are_tallied_by_count_and_weight() {
+    let mut claim = claim(Pubkey::new_unique(), 100);
+    claim.tally_vote(&VoteOption::Yes, 30).unwrap();
+    claim.tally_vote(&VoteOption::Yes, 5).unwrap();
+    claim.tally_vote(&VoteOption::No, 20).unwrap();
+    assert_eq!((claim.yes_votes, claim.yes_weight), (2, 35));
+    assert_eq!((claim.no_votes, claim.no_weight), (1, 20));
+
+    claim.no_weight = u64::MAX;
+    assert!(claim.tally_vote(&VoteOption::No, 1).is_err());
+}
@@ -1,119 +1,220 @@
-use num_bigint::BigInt;
 use crate::r1cs::{R1CS};
-use crate::field::FieldElement;
-use crate::proof::Proof;
+use crate::error::{Result, ZkError};
+use crate::field::{Bn254Fr, FieldElement, FieldParams};
 
-pub enum Gate {
+pub enum Gate<F: FieldParams = Bn254Fr> {
+    /// `a + b = output`, lowered to `(a + b) * 1 = output`.
     Add(usize, usize, usize),
-    Mul(usize, usize, usize), 
+    /// `a * b = output`.
+    Mul(usize, usize, usize),
+    /// `output = sum(coeff * wire)`. Constants are combinations of the one wire.
+    Linear(Vec<(usize, FieldElement<F>)>, usize),
+    /// A raw `<A, w> * <B, w> = <C, w>` constraint between existing wires.
+    Enforce(Vec<(usize, FieldElement<F>)>, Vec<(usize, FieldElement<F>)>, Vec<(usize, FieldElement<F>)>),
 }
 
-pub struct Circuit {
-    inputs: Vec<FieldElement>, 
-    gates: Vec<Gate>,
-    outputs: Vec<FieldElement>, 
-    modulus: BigInt, 
+/// Handle to a wire of a `Circuit`. Gates take handles and return a new one for their output.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Wire(usize);
+
+impl Wire {
+    /// The wire that always carries 1.
+    pub const ONE: Wire = Wire(0);
+
+    pub fn index(self) -> usize {
+        self.0
+    }
+}
+
+/// Whether the verifier sees a wire's value. Everything computed by gates starts out private and can
+/// be published with `Circuit::expose`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Visibility {
+    Public,
+    Private,
+}
+
+/// An arithmetic circuit over `F`. Wire values are computed as gates are added, so the witness is
+/// always the one the gates enforce:
+///
+/// ```
+/// # use zero_knowledge_proofs::circuit::Circuit;
+/// # use zero_knowledge_proofs::field::FieldElement;
+/// let mut cs: Circuit = Circuit::new();
+/// let a = cs.private_input(FieldElement::from_u64(3));
+/// let b = cs.private_input(FieldElement::from_u64(4));
+/// let x = cs.public_input(FieldElement::from_u64(5));
+/// let c = cs.mul(a, b);
+/// let d = cs.add(c, x);
+/// assert_eq!(cs.value(d), FieldElement::from_u64(17));
+/// ```
+pub struct Circuit<F: FieldParams = Bn254Fr> {
+    wires: Vec<FieldElement<F>>,
+    visibility: Vec<Visibility>,
+    gates: Vec<Gate<F>>,
+    outputs: Vec<FieldElement<F>>,
 }
 
-impl Circuit {
+impl<F: FieldParams> Default for Circuit<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: FieldParams> Circuit<F> {
     pub fn new() -> Self {
-        let default_modulus = BigInt::from(1_000_000_007); // Default modulus
         Circuit {
-            inputs: Vec::new(),
+            wires: vec![FieldElement::one()],
+            visibility: vec![Visibility::Public],
             gates: Vec::new(),
             outputs: Vec::new(),
-            modulus: default_modulus,
         }
     }
 
-    pub fn add_input(&mut self, value: FieldElement) -> usize { 
-        let index = self.inputs.len();
-        self.inputs.push(value);
-        index
+    /// Add a private wire holding `value`. Unlike the builder methods, nothing checks that gates added
+    /// with `add_gate` agree with the values given here.
+    pub fn add_input(&mut self, value: FieldElement<F>) -> usize {
+        self.push_wire(value, Visibility::Private).index()
     }
 
-    pub fn add_gate(&mut self, gate: Gate) {
+    pub fn add_gate(&mut self, gate: Gate<F>) {
         self.gates.push(gate);
     }
 
-    pub fn set_output(&mut self, value: FieldElement) { 
+    pub fn set_output(&mut self, value: FieldElement<F>) {
         self.outputs.push(value);
     }
 
-    pub fn get_input(&self, index: usize) -> Option<&FieldElement> {
-        self.inputs.get(index)
+    pub fn get_input(&self, index: usize) -> Option<&FieldElement<F>> {
+        self.wires.get(index)
     }
 
+    pub fn public_input(&mut self, value: FieldElement<F>) -> Wire {
+        self.push_wire(value, Visibility::Public)
+    }
 
-    pub fn generate_proof(&self, proof_file: &str) {
-        // Ensure inputs are added before generating proof
-        if self.inputs.is_empty() {
-            panic!("No inputs available to generate proof.");
-        }
+    pub fn private_input(&mut self, value: FieldElement<F>) -> Wire {
+        self.push_wire(value, Visibility::Private)
+    }
 
-        let mut r1cs = R1CS::new();
+    /// A private wire that no gate constrains yet. Gadgets use hints for values that are cheaper to
+    /// check than to compute in-circuit, such as bits or inverses, and must constrain them themselves.
+    pub fn hint(&mut self, value: FieldElement<F>) -> Wire {
+        self.push_wire(value, Visibility::Private)
+    }
+
+    /// Publish a computed wire, e.g. a result the verifier should learn.
+    pub fn expose(&mut self, wire: Wire) {
+        self.visibility[wire.index()] = Visibility::Public;
+    }
+
+    pub fn value(&self, wire: Wire) -> FieldElement<F> {
+        self.wires[wire.index()]
+    }
+
+    pub fn constant(&mut self, value: FieldElement<F>) -> Wire {
+        self.linear_combination(&[(value, Wire::ONE)])
+    }
+
+    pub fn add(&mut self, a: Wire, b: Wire) -> Wire {
+        self.linear_combination(&[(FieldElement::one(), a), (FieldElement::one(), b)])
+    }
+
+    pub fn sub(&mut self, a: Wire, b: Wire) -> Wire {
+        self.linear_combination(&[(FieldElement::one(), a), (-FieldElement::one(), b)])
+    }
 
-        // Add variables to R1CS
-        for input in &self.inputs {
-            r1cs.add_variable(input.clone()); // input is of type FieldElement
+    pub fn scale(&mut self, a: Wire, coeff: FieldElement<F>) -> Wire {
+        self.linear_combination(&[(coeff, a)])
+    }
+
+    pub fn mul(&mut self, a: Wire, b: Wire) -> Wire {
+        let output = self.push_wire(self.value(a) * self.value(b), Visibility::Private);
+        self.gates.push(Gate::Mul(a.index(), b.index(), output.index()));
+        output
+    }
+
+    /// `sum(coeff * wire)`, enforced by a single constraint however many terms there are.
+    pub fn linear_combination(&mut self, terms: &[(FieldElement<F>, Wire)]) -> Wire {
+        let value = terms.iter().map(|(coeff, wire)| *coeff * self.value(*wire)).sum();
+        let output = self.push_wire(value, Visibility::Private);
+        let terms = terms.iter().map(|(coeff, wire)| (wire.index(), *coeff)).collect();
+        self.gates.push(Gate::Linear(terms, output.index()));
+        output
+    }
+
+    /// Constrain `<a> * <b> = <c>` for linear combinations of existing wires, without creating a new one.
+    pub fn enforce(
+        &mut self,
+        a: &[(FieldElement<F>, Wire)],
+        b: &[(FieldElement<F>, Wire)],
+        c: &[(FieldElement<F>, Wire)],
+    ) {
+        let terms = |combination: &[(FieldElement<F>, Wire)]| {
+            combination.iter().map(|(coeff, wire)| (wire.index(), *coeff)).collect()
+        };
+        self.gates.push(Gate::Enforce(terms(a), terms(b), terms(c)));
+    }
+
+    pub fn assert_equal(&mut self, a: Wire, b: Wire) {
+        self.enforce(&[(FieldElement::one(), a), (-FieldElement::one(), b)], &[(FieldElement::one(), Wire::ONE)], &[]);
+    }
+
+    /// Number of public wires, not counting the one wire.
+    pub fn num_public(&self) -> usize {
+        self.visibility[1..].iter().filter(|visibility| **visibility == Visibility::Public).count()
+    }
+
+    /// Lower the circuit to R1CS. Variables are laid out as the one wire, then the public wires, then
+    /// the private ones, each group in the order the wires were created. Fails with
+    /// `ZkError::InvalidWire` if a gate added with `add_gate` refers to a wire that does not exist.
+    pub fn to_r1cs(&self) -> Result<R1CS<F>> {
+        let mut r1cs = R1CS::new();
+        let mut variables = vec![R1CS::<F>::ONE; self.wires.len()];
+        for visibility in [Visibility::Public, Visibility::Private] {
+            let wires = self.wires.iter().zip(&self.visibility).enumerate().skip(1);
+            for (index, (value, wire_visibility)) in wires {
+                if *wire_visibility == visibility {
+                    variables[index] = match visibility {
+                        Visibility::Public => r1cs.add_public_input(*value)?,
+                        Visibility::Private => r1cs.add_variable(*value),
+                    };
+                }
+            }
         }
 
-        // Process each gate and add constraints to R1CS
+        let one = FieldElement::one();
+        let variable = |wire: &usize| variables.get(*wire).copied().ok_or(ZkError::InvalidWire(*wire));
+        let terms = |combination: &[(usize, FieldElement<F>)]| -> Result<Vec<(usize, FieldElement<F>)>> {
+            combination.iter().map(|(wire, coeff)| Ok((variable(wire)?, *coeff))).collect()
+        };
         for gate in &self.gates {
             match gate {
                 Gate::Add(a, b, output) => {
-                    r1cs.add_constraint(
-                        &[
-                            (r1cs.variables[*a].index, FieldElement::new(BigInt::from(1))), // Extract index
-                        ],
-                        &[
-                            (r1cs.variables[*b].index, FieldElement::new(BigInt::from(1))), // Extract index
-                        ],
-                        &[
-                            (r1cs.variables[*output].index, FieldElement::new(BigInt::from(1))), // Extract index
-                        ],
-                        &self.modulus, // Pass modulus dynamically
-                    );
+                    r1cs.add_linear_constraint(&[(variable(a)?, one), (variable(b)?, one)], variable(output)?)?;
                 },
                 Gate::Mul(a, b, output) => {
                     r1cs.add_constraint(
-                        &[
-                            (r1cs.variables[*a].index, FieldElement::new(BigInt::from(1))), // Extract index
-                        ],
-                        &[
-                            (r1cs.variables[*b].index, FieldElement::new(BigInt::from(1))), // Extract index
-                        ],
-                        &[
-                            (r1cs.variables[*output].index, FieldElement::new(BigInt::from(1))), // Extract index
-                        ],
-                        &self.modulus, // Pass modulus dynamically
-                    );
+                        &[(variable(a)?, one)],
+                        &[(variable(b)?, one)],
+                        &[(variable(output)?, one)],
+                    )?;
+                },
+                Gate::Linear(combination, output) => {
+                    r1cs.add_linear_constraint(&terms(combination)?, variable(output)?)?;
+                },
+                Gate::Enforce(a, b, c) => {
+                    r1cs.add_constraint(&terms(a)?, &terms(b)?, &terms(c)?)?;
                 },
             }
         }
 
-        r1cs.save_to_binary("r1cs_file.bin");
-
-        let witness = r1cs.generate_witness();
-        let proof = r1cs.generate_proof(&witness);
-
-        proof.save_to_binary(proof_file).expect("failed to save the proof");
+        Ok(r1cs)
     }
 
-    pub fn verify_proof(&self, proof_file: &str) -> bool {
-        let proof_data = std::fs::read(proof_file).expect("Could not read proof file");
-
-        let proof = bincode::deserialize::<Proof>(&proof_data).expect("Failed to deserialize proof");
-
-        let witness: Vec<FieldElement> = proof.witness.iter()
-            .map(|value| FieldElement::new(value.clone()))
-            .collect();
-
-        let r1cs = R1CS::load_from_binary("r1cs_file.bin");
-
-        let is_valid = r1cs.verify_witness(&witness);
-
-        println!("Proof verification result: {}", is_valid);
-        is_valid
+    fn push_wire(&mut self, value: FieldElement<F>, visibility: Visibility) -> Wire {
+        self.wires.push(value);
+        self.visibility.push(visibility);
+        Wire(self.wires.len() - 1)
     }
 }
@@ -0,0 +1,95 @@
+use std::fmt;
+use std::io;
+use ark_relations::r1cs::SynthesisError;
+
+/// Everything that can go wrong in this crate. Callers that need to abort can still do so, but a bad
+/// proof, witness or file never takes the process down on its own.
+#[derive(Debug)]
+pub enum ZkError {
+    /// The witness breaks at least one constraint.
+    Unsatisfied,
+    /// Inversion of, or division by, zero.
+    NoInverse,
+    /// A gate or constraint refers to a wire or variable that does not exist.
+    InvalidWire(usize),
+    /// A witness, input list or proof component of the wrong length.
+    LengthMismatch { expected: usize, actual: usize },
+    /// Data over one field loaded as another.
+    ModulusMismatch { expected: String, found: String },
+    /// More evaluation points than the field has a power-of-two domain for.
+    DomainSize { required: usize, available: usize },
+    /// Parameters a construction does not support, such as a Poseidon width or a gadget bit width.
+    InvalidParameters(String),
+    /// `insert` of a key already in a sparse Merkle tree.
+    KeyExists,
+    /// `update` or `delete` of a key missing from a sparse Merkle tree.
+    KeyNotFound,
+    /// A setup ceremony contribution, counted from zero, that does not check out against the ones
+    /// before it.
+    InvalidContribution(usize),
+    /// A file whose body does not match the checksum in its header.
+    ChecksumMismatch,
+    /// A file written by an incompatible version of this crate.
+    UnsupportedVersion { found: u32, supported: u32 },
+    /// Groth16 setup or proving failed for a reason other than an unsatisfied constraint.
+    Synthesis(SynthesisError),
+    Io(io::Error),
+    Serialization(String),
+    Deserialization(String),
+}
+
+pub type Result<T, E = ZkError> = std::result::Result<T, E>;
+
+impl fmt::Display for ZkError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ZkError::Unsatisfied => write!(f, "the witness does not satisfy the constraints"),
+            ZkError::NoInverse => write!(f, "zero has no inverse"),
+            ZkError::InvalidWire(index) => write!(f, "wire {index} does not exist"),
+            ZkError::LengthMismatch { expected, actual } => write!(f, "expected {expected} values, got {actual}"),
+            ZkError::ModulusMismatch { expected, found } => {
+                write!(f, "expected data over {expected}, found data over {found}")
+            },
+            ZkError::DomainSize { required, available } => {
+                write!(f, "{required} evaluation points needed but the field's largest domain has {available}")
+            },
+            ZkError::InvalidParameters(reason) => write!(f, "invalid parameters: {reason}"),
+            ZkError::KeyExists => write!(f, "key is already in the tree"),
+            ZkError::KeyNotFound => write!(f, "key is not in the tree"),
+            ZkError::InvalidContribution(index) => write!(f, "contribution {index} to the setup is invalid"),
+            ZkError::ChecksumMismatch => write!(f, "the file is corrupt: checksum mismatch"),
+            ZkError::UnsupportedVersion { found, supported } => {
+                write!(f, "file format version {found} is not supported, expected {supported}")
+            },
+            ZkError::Synthesis(error) => write!(f, "Groth16 synthesis failed: {error}"),
+            ZkError::Io(error) => write!(f, "I/O error: {error}"),
+            ZkError::Serialization(reason) => write!(f, "serialization failed: {reason}"),
+            ZkError::Deserialization(reason) => write!(f, "deserialization failed: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for ZkError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ZkError::Synthesis(error) => Some(error),
+            ZkError::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for ZkError {
+    fn from(error: io::Error) -> Self {
+        ZkError::Io(error)
+    }
+}
+
+impl From<SynthesisError> for ZkError {
+    fn from(error: SynthesisError) -> Self {
+        match error {
+            SynthesisError::Unsatisfiable => ZkError::Unsatisfied,
+            other => ZkError::Synthesis(other),
+        }
+    }
+}
@@ -1,109 +1,607 @@
-use std::ops::AddAssign;
-use num_bigint::BigInt;
-use num_traits::{One, Zero};
-use std::ops::{Add, Mul};
-use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::hash::Hash;
+use std::marker::PhantomData;
+use std::iter::{Product, Sum};
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+use num_bigint::{BigInt, BigUint, Sign};
+use num_integer::Integer;
+use num_traits::Zero;
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use crate::error::ZkError;
 
-#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
-pub struct FieldElement {
-    value: BigInt,
-    modulus: BigInt,
+/// Number of 64-bit limbs in an element. Every supported modulus fits below 2^255.
+pub const LIMBS: usize = 4;
+
+/// A prime field, described by its modulus. The Montgomery constants are derived from it at compile
+/// time, so adding a field only takes the modulus and a name.
+pub trait FieldParams: 'static + Copy + Clone + fmt::Debug + Default + PartialEq + Eq + Hash + Send + Sync {
+    /// Little-endian limbs of the (odd) modulus.
+    const MODULUS: [u64; LIMBS];
+    const NAME: &'static str;
+    /// A generator of the multiplicative group. Roots of unity for NTTs are powers of it, and it
+    /// shifts NTT domains onto cosets.
+    const GENERATOR: u64;
+
+    /// `-MODULUS^-1 mod 2^64`.
+    const INV: u64 = montgomery_inv(Self::MODULUS[0]);
+    /// `2^256 mod MODULUS`, i.e. one in Montgomery form.
+    const R: [u64; LIMBS] = pow2_mod(256, &Self::MODULUS);
+    /// `2^512 mod MODULUS`, used to move values into Montgomery form.
+    const R2: [u64; LIMBS] = pow2_mod(512, &Self::MODULUS);
+}
+
+/// Scalar field of BN254, the curve the Groth16 prover and the on-chain verifier run over.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Bn254Fr;
+
+impl FieldParams for Bn254Fr {
+    // 21888242871839275222246405745257275088548364400416034343698204186575808495617
+    const MODULUS: [u64; LIMBS] = [
+        0x43e1f593f0000001,
+        0x2833e84879b97091,
+        0xb85045b68181585d,
+        0x30644e72e131a029,
+    ];
+    const NAME: &'static str = "bn254-fr";
+    const GENERATOR: u64 = 5;
+}
+
+/// Scalar field of BLS12-381.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Bls12_381Fr;
+
+impl FieldParams for Bls12_381Fr {
+    // 52435875175126190479447740508185965837690552500527637822603658699938581184513
+    const MODULUS: [u64; LIMBS] = [
+        0xffffffff00000001,
+        0x53bda402fffe5bfe,
+        0x3339d80809a1d805,
+        0x73eda753299d7d48,
+    ];
+    const NAME: &'static str = "bls12-381-fr";
+    const GENERATOR: u64 = 7;
+}
+
+/// The 64-bit Goldilocks field, 2^64 - 2^32 + 1.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Goldilocks;
+
+impl FieldParams for Goldilocks {
+    const MODULUS: [u64; LIMBS] = [0xffffffff00000001, 0, 0, 0];
+    const NAME: &'static str = "goldilocks";
+    const GENERATOR: u64 = 7;
+}
+
+/// An element of the field `F`, kept reduced and in Montgomery form. Elements of different fields are
+/// different types, so they cannot be mixed by accident.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FieldElement<F: FieldParams = Bn254Fr> {
+    limbs: [u64; LIMBS],
+    field: PhantomData<F>,
 }
 
-impl FieldElement {
+impl<F: FieldParams> FieldElement<F> {
+    /// Reduce `value` into the field; negative values wrap around the modulus.
     pub fn new(value: BigInt) -> Self {
-        let default_modulus = BigInt::from(1_000_000_007);
-        let normalized_value = value.clone() % &default_modulus;
-        FieldElement { value: normalized_value, modulus: default_modulus }
+        let reduced = value.mod_floor(&Self::modulus());
+        Self::from_canonical(bigint_to_limbs(&reduced))
+    }
+
+    pub fn zero() -> Self {
+        Self::from_montgomery([0; LIMBS])
     }
 
+    pub fn one() -> Self {
+        Self::from_montgomery(F::R)
+    }
+
+    pub fn from_u64(value: u64) -> Self {
+        Self::from_canonical([value, 0, 0, 0])
+    }
+
+    pub fn modulus() -> BigInt {
+        limbs_to_bigint(&F::MODULUS)
+    }
+
+    /// The canonical representative, in `[0, modulus)`.
     pub fn get_value(&self) -> BigInt {
-        self.value.clone()
+        limbs_to_bigint(&self.to_canonical())
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.limbs == [0; LIMBS]
+    }
+
+    pub fn add(&self, other: &FieldElement<F>) -> FieldElement<F> {
+        let (sum, carry) = add_limbs(&self.limbs, &other.limbs);
+        Self::from_montgomery(reduce_once::<F>(sum, carry))
+    }
+
+    pub fn sub(&self, other: &FieldElement<F>) -> FieldElement<F> {
+        let (difference, borrow) = sub_limbs(&self.limbs, &other.limbs);
+        if borrow {
+            Self::from_montgomery(add_limbs(&difference, &F::MODULUS).0)
+        } else {
+            Self::from_montgomery(difference)
+        }
+    }
+
+    pub fn mul(&self, other: &FieldElement<F>) -> FieldElement<F> {
+        Self::from_montgomery(montgomery_mul::<F>(&self.limbs, &other.limbs))
+    }
+
+    pub fn inv(&self) -> Result<FieldElement<F>, ZkError> {
+        if self.is_zero() {
+            return Err(ZkError::NoInverse);
+        }
+        Ok(self.inv_or_zero())
+    }
+
+    /// The inverse, or zero for zero. Fermat: `a^(p - 2) = a^-1` in a prime field, and `0^(p - 2) = 0`.
+    pub(crate) fn inv_or_zero(&self) -> FieldElement<F> {
+        let (exponent, _) = sub_limbs(&F::MODULUS, &[2, 0, 0, 0]);
+        self.pow_limbs(&exponent)
+    }
+
+    pub fn negate(&self) -> FieldElement<F> {
+        Self::zero().sub(self)
+    }
+
+    pub fn div(&self, other: &FieldElement<F>) -> Result<FieldElement<F>, ZkError> {
+        Ok(self.mul(&other.inv()?))
     }
 
-    pub fn get_modulus(&self) -> &BigInt {
-        &self.modulus
+    /// Behind `/` and `/=`, which panic on division by zero like integer division does. Use `div` to
+    /// get an error instead.
+    fn div_or_panic(&self, other: &FieldElement<F>) -> FieldElement<F> {
+        match self.div(other) {
+            Ok(quotient) => quotient,
+            Err(_) => panic!("division by zero in the {} field", F::NAME),
+        }
+    }
+
+    pub fn square(&self) -> FieldElement<F> {
+        self.mul(self)
+    }
+
+    pub fn pow(&self, exponent: u64) -> FieldElement<F> {
+        self.pow_limbs(&[exponent, 0, 0, 0])
+    }
+
+    /// Euler's criterion: 1 for a non-zero square, -1 for a non-square and 0 for zero.
+    pub fn legendre(&self) -> i8 {
+        let symbol = self.pow_limbs(&shr_limbs(&sub_limbs(&F::MODULUS, &[1, 0, 0, 0]).0, 1));
+        if symbol.is_zero() {
+            0
+        } else if symbol == Self::one() {
+            1
+        } else {
+            -1
+        }
+    }
+
+    /// A square root by Tonelli–Shanks, or `None` if the element is not a square. Of the two roots,
+    /// the one returned is unspecified; negate it for the other.
+    pub fn sqrt(&self) -> Option<FieldElement<F>> {
+        match self.legendre() {
+            0 => return Some(Self::zero()),
+            -1 => return None,
+            _ => {}
+        }
+
+        // p - 1 = q * 2^s with q odd. The generator is a non-residue, so its q-th power has order 2^s.
+        let (q, s) = odd_part_of_order::<F>();
+        let mut m = s;
+        let mut c = Self::multiplicative_generator().pow_limbs(&q);
+        let mut t = self.pow_limbs(&q);
+        let mut root = self.pow_limbs(&shr_limbs(&add_limbs(&q, &[1, 0, 0, 0]).0, 1));
+        while t != Self::one() {
+            // Least i with t^(2^i) = 1; it is below m because t has order dividing 2^(m - 1).
+            let mut i = 0;
+            let mut t_power = t;
+            while t_power != Self::one() {
+                t_power = t_power.square();
+                i += 1;
+            }
+
+            let mut b = c;
+            for _ in 0..m - i - 1 {
+                b = b.square();
+            }
+            m = i;
+            c = b.square();
+            t *= c;
+            root *= b;
+        }
+        Some(root)
+    }
+
+    pub fn multiplicative_generator() -> FieldElement<F> {
+        Self::from_u64(F::GENERATOR)
     }
 
-    pub fn add(&self, other: &FieldElement) -> FieldElement {
-        assert_eq!(self.modulus, other.modulus);
-        FieldElement::new(self.value.clone() + other.value.clone())
+    /// Largest `s` with `2^s` dividing `p - 1`, i.e. the largest power-of-two NTT the field supports.
+    pub fn two_adicity() -> u32 {
+        odd_part_of_order::<F>().1
     }
 
-    pub fn sub(&self, other: &FieldElement) -> FieldElement {
-        assert_eq!(self.modulus, other.modulus);
-        FieldElement::new(self.value.clone() - other.value.clone())
+    /// A primitive `2^log_size`-th root of unity, or `None` if `log_size` exceeds the two-adicity.
+    pub fn root_of_unity(log_size: u32) -> Option<FieldElement<F>> {
+        let (q, s) = odd_part_of_order::<F>();
+        if log_size > s {
+            return None;
+        }
+        let mut root = Self::multiplicative_generator().pow_limbs(&q);
+        for _ in log_size..s {
+            root = root.square();
+        }
+        Some(root)
     }
 
-    pub fn mul(&self, other: &FieldElement) -> FieldElement {
-        assert_eq!(self.modulus, other.modulus);
-        FieldElement::new(&self.value * &other.value % &self.modulus)
+    /// Canonical big-endian encoding.
+    pub fn to_bytes_be(&self) -> [u8; 32] {
+        let mut bytes = self.to_bytes_le();
+        bytes.reverse();
+        bytes
     }
 
-    pub fn inv(&self) -> FieldElement {
-        let (gcd, x, _) = self.extended_gcd(&self.value, &self.modulus);
-        if gcd != BigInt::one() {
-            panic!("Inverse does not exist");
+    /// Canonical little-endian encoding.
+    pub fn to_bytes_le(&self) -> [u8; 32] {
+        let mut bytes = [0u8; 32];
+        for (chunk, limb) in bytes.chunks_exact_mut(8).zip(self.to_canonical()) {
+            chunk.copy_from_slice(&limb.to_le_bytes());
         }
-        // Normalize the inverse to be positive
-        let normalized_inverse = x % &self.modulus;
-        FieldElement::new(normalized_inverse)
+        bytes
     }
 
-    pub fn negate(&self) -> FieldElement {
-        FieldElement::new(&self.modulus - &self.value)
+    /// Decode a canonical big-endian encoding; values at or above the modulus are rejected.
+    pub fn from_bytes_be(bytes: &[u8; 32]) -> Option<FieldElement<F>> {
+        let mut le = *bytes;
+        le.reverse();
+        Self::from_bytes_le(&le)
     }
 
-    fn extended_gcd(&self, a: &BigInt, b: &BigInt) -> (BigInt, BigInt, BigInt) {
-        if *b == BigInt::zero() {
-            return (a.clone(), BigInt::one(), BigInt::zero());
+    /// Decode a canonical little-endian encoding; values at or above the modulus are rejected.
+    pub fn from_bytes_le(bytes: &[u8; 32]) -> Option<FieldElement<F>> {
+        let mut limbs = [0u64; LIMBS];
+        for (limb, chunk) in limbs.iter_mut().zip(bytes.chunks_exact(8)) {
+            *limb = u64::from_le_bytes(chunk.try_into().unwrap());
         }
-        let (gcd, x1, y1) = self.extended_gcd(b, &(a % b));
-        let x = y1.clone();
-        let y = x1 - (a / b) * &y1;
-        (gcd, x, y)
+        less_than(&limbs, &F::MODULUS).then(|| Self::from_canonical(limbs))
+    }
+
+    fn pow_limbs(&self, exponent: &[u64; LIMBS]) -> FieldElement<F> {
+        // Start at the highest set bit, so small exponents such as S-box powers stay cheap.
+        let bits = exponent
+            .iter()
+            .rposition(|limb| *limb != 0)
+            .map_or(0, |top| top * 64 + 64 - exponent[top].leading_zeros() as usize);
+        let mut result = Self::one();
+        for bit in (0..bits).rev() {
+            result = result.mul(&result);
+            if (exponent[bit / 64] >> (bit % 64)) & 1 == 1 {
+                result = result.mul(self);
+            }
+        }
+        result
+    }
+
+    fn from_montgomery(limbs: [u64; LIMBS]) -> Self {
+        FieldElement { limbs, field: PhantomData }
+    }
+
+    /// `limbs` must already be below the modulus.
+    pub(crate) fn from_canonical(limbs: [u64; LIMBS]) -> Self {
+        Self::from_montgomery(montgomery_mul::<F>(&limbs, &F::R2))
+    }
+
+    pub(crate) fn to_canonical(self) -> [u64; LIMBS] {
+        montgomery_mul::<F>(&self.limbs, &[1, 0, 0, 0])
     }
 }
-impl AddAssign for FieldElement {
-    fn add_assign(&mut self, other: FieldElement) {
-        assert_eq!(self.modulus, other.modulus, "Moduli must match for addition");
-        self.value = (self.value.clone() + other.value) % &self.modulus; 
+
+impl<F: FieldParams> Default for FieldElement<F> {
+    fn default() -> Self {
+        Self::zero()
     }
 }
 
-impl Add for FieldElement {
-    type Output = FieldElement;
+impl<F: FieldParams> fmt::Debug for FieldElement<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}({})", F::NAME, self.get_value())
+    }
+}
 
-    fn add(self, other: FieldElement) -> FieldElement {
-        let mut result = self.clone(); 
-        result += other; 
-        result
+impl<F: FieldParams> fmt::Display for FieldElement<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.get_value())
+    }
+}
+
+// Serialized as canonical limbs, so the encoding does not depend on the Montgomery constants.
+impl<F: FieldParams> Serialize for FieldElement<F> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.to_canonical().serialize(serializer)
+    }
+}
+
+impl<'de, F: FieldParams> Deserialize<'de> for FieldElement<F> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let limbs = <[u64; LIMBS]>::deserialize(deserializer)?;
+        if !less_than(&limbs, &F::MODULUS) {
+            return Err(D::Error::custom(format!("value is not reduced modulo the {} modulus", F::NAME)));
+        }
+        Ok(Self::from_canonical(limbs))
     }
 }
 
-// Implementing Add for references
-impl Add for &FieldElement {
-    type Output = FieldElement;
+/// Implements `$trait` for every owned/borrowed combination of operands, plus `$assign_trait` with
+/// either an owned or a borrowed right-hand side, all in terms of the inherent method `$method`.
+macro_rules! impl_binary_op {
+    ($trait:ident, $fn:ident, $assign_trait:ident, $assign_fn:ident, $method:ident) => {
+        impl<F: FieldParams> $trait for FieldElement<F> {
+            type Output = FieldElement<F>;
 
-    fn add(self, other: &FieldElement) -> FieldElement {
-        assert_eq!(self.modulus, other.modulus, "Moduli must match for addition");
-        FieldElement::new((self.value.clone() + other.value.clone()) % &self.modulus)
+            fn $fn(self, rhs: FieldElement<F>) -> FieldElement<F> {
+                FieldElement::$method(&self, &rhs)
+            }
+        }
+
+        impl<F: FieldParams> $trait<&FieldElement<F>> for FieldElement<F> {
+            type Output = FieldElement<F>;
+
+            fn $fn(self, rhs: &FieldElement<F>) -> FieldElement<F> {
+                FieldElement::$method(&self, rhs)
+            }
+        }
+
+        impl<F: FieldParams> $trait<FieldElement<F>> for &FieldElement<F> {
+            type Output = FieldElement<F>;
+
+            fn $fn(self, rhs: FieldElement<F>) -> FieldElement<F> {
+                FieldElement::$method(self, &rhs)
+            }
+        }
+
+        impl<F: FieldParams> $trait for &FieldElement<F> {
+            type Output = FieldElement<F>;
+
+            fn $fn(self, rhs: &FieldElement<F>) -> FieldElement<F> {
+                FieldElement::$method(self, rhs)
+            }
+        }
+
+        impl<F: FieldParams> $assign_trait for FieldElement<F> {
+            fn $assign_fn(&mut self, rhs: FieldElement<F>) {
+                *self = FieldElement::$method(self, &rhs);
+            }
+        }
+
+        impl<F: FieldParams> $assign_trait<&FieldElement<F>> for FieldElement<F> {
+            fn $assign_fn(&mut self, rhs: &FieldElement<F>) {
+                *self = FieldElement::$method(self, rhs);
+            }
+        }
+    };
+}
+
+impl_binary_op!(Add, add, AddAssign, add_assign, add);
+impl_binary_op!(Sub, sub, SubAssign, sub_assign, sub);
+impl_binary_op!(Mul, mul, MulAssign, mul_assign, mul);
+impl_binary_op!(Div, div, DivAssign, div_assign, div_or_panic);
+
+impl<F: FieldParams> Neg for FieldElement<F> {
+    type Output = FieldElement<F>;
+
+    fn neg(self) -> FieldElement<F> {
+        self.negate()
+    }
+}
+
+impl<F: FieldParams> Neg for &FieldElement<F> {
+    type Output = FieldElement<F>;
+
+    fn neg(self) -> FieldElement<F> {
+        self.negate()
+    }
+}
+
+impl<F: FieldParams> Mul<&BigInt> for FieldElement<F> {
+    type Output = FieldElement<F>;
+
+    fn mul(self, rhs: &BigInt) -> FieldElement<F> {
+        FieldElement::mul(&self, &FieldElement::new(rhs.clone()))
+    }
+}
+
+impl<F: FieldParams> Mul<BigInt> for FieldElement<F> {
+    type Output = FieldElement<F>;
+
+    fn mul(self, rhs: BigInt) -> FieldElement<F> {
+        self * &rhs
+    }
+}
+
+impl<F: FieldParams> From<u64> for FieldElement<F> {
+    fn from(value: u64) -> Self {
+        Self::from_u64(value)
+    }
+}
+
+impl<F: FieldParams> Sum for FieldElement<F> {
+    fn sum<I: Iterator<Item = FieldElement<F>>>(iter: I) -> Self {
+        iter.fold(Self::zero(), |acc, value| acc + value)
+    }
+}
+
+impl<F: FieldParams> Product for FieldElement<F> {
+    fn product<I: Iterator<Item = FieldElement<F>>>(iter: I) -> Self {
+        iter.fold(Self::one(), |acc, value| acc * value)
     }
 }
 
-// Implementing Mul trait for FieldElement
-impl Mul<&BigInt> for FieldElement {
-    type Output = FieldElement;
+/// Invert every element with a single field inversion (Montgomery's trick). Zeroes have no inverse and
+/// are left as zero.
+pub fn batch_inverse<F: FieldParams>(values: &[FieldElement<F>]) -> Vec<FieldElement<F>> {
+    // prefix[i] is the product of the non-zero values before index i.
+    let mut prefix = Vec::with_capacity(values.len());
+    let mut running = FieldElement::one();
+    for value in values {
+        prefix.push(running);
+        if !value.is_zero() {
+            running *= value;
+        }
+    }
+
+    let mut inverse = running.inv_or_zero();
+    let mut inverses = vec![FieldElement::zero(); values.len()];
+    for (index, value) in values.iter().enumerate().rev() {
+        if value.is_zero() {
+            continue;
+        }
+        inverses[index] = inverse * prefix[index];
+        inverse *= value;
+    }
+    inverses
+}
+
+fn limbs_to_bigint(limbs: &[u64; LIMBS]) -> BigInt {
+    let digits: Vec<u32> = limbs.iter().flat_map(|limb| [*limb as u32, (limb >> 32) as u32]).collect();
+    BigInt::from_biguint(Sign::Plus, BigUint::new(digits))
+}
+
+/// `value` must be non-negative and below 2^256.
+fn bigint_to_limbs(value: &BigInt) -> [u64; LIMBS] {
+    let mut limbs = [0u64; LIMBS];
+    if value.is_zero() {
+        return limbs;
+    }
+    for (limb, digit) in limbs.iter_mut().zip(value.magnitude().iter_u64_digits()) {
+        *limb = digit;
+    }
+    limbs
+}
+
+const fn less_than(a: &[u64; LIMBS], b: &[u64; LIMBS]) -> bool {
+    let mut i = LIMBS;
+    while i > 0 {
+        i -= 1;
+        if a[i] != b[i] {
+            return a[i] < b[i];
+        }
+    }
+    false
+}
 
-    fn mul(self, rhs: &BigInt) -> FieldElement {
-        FieldElement::new((self.value.clone() * rhs) % &self.modulus) // Perform multiplication and normalize
+const fn shr_limbs(value: &[u64; LIMBS], shift: u32) -> [u64; LIMBS] {
+    let mut shifted = [0u64; LIMBS];
+    let mut i = 0;
+    while i < LIMBS {
+        shifted[i] = value[i] >> shift;
+        if i + 1 < LIMBS && shift > 0 {
+            shifted[i] |= value[i + 1] << (64 - shift);
+        }
+        i += 1;
     }
+    shifted
 }
 
-impl Mul<BigInt> for FieldElement {
-    type Output = FieldElement;
+/// `(q, s)` with `p - 1 = q * 2^s` and `q` odd.
+fn odd_part_of_order<F: FieldParams>() -> ([u64; LIMBS], u32) {
+    let mut q = sub_limbs(&F::MODULUS, &[1, 0, 0, 0]).0;
+    let mut s = 0u32;
+    while q[0] & 1 == 0 {
+        q = shr_limbs(&q, 1);
+        s += 1;
+    }
+    (q, s)
+}
 
-    fn mul(self, rhs: BigInt) -> FieldElement {
-        self * &rhs 
+const fn add_limbs(a: &[u64; LIMBS], b: &[u64; LIMBS]) -> ([u64; LIMBS], bool) {
+    let mut sum = [0u64; LIMBS];
+    let mut carry = 0u128;
+    let mut i = 0;
+    while i < LIMBS {
+        let total = a[i] as u128 + b[i] as u128 + carry;
+        sum[i] = total as u64;
+        carry = total >> 64;
+        i += 1;
     }
-}
\ No newline at end of file
+    (sum, carry != 0)
+}
+
+const fn sub_limbs(a: &[u64; LIMBS], b: &[u64; LIMBS]) -> ([u64; LIMBS], bool) {
+    let mut difference = [0u64; LIMBS];
+    let mut borrow = false;
+    let mut i = 0;
+    while i < LIMBS {
+        let (partial, underflow_b) = a[i].overflowing_sub(b[i]);
+        let (partial, underflow_borrow) = partial.overflowing_sub(borrow as u64);
+        difference[i] = partial;
+        borrow = underflow_b || underflow_borrow;
+        i += 1;
+    }
+    (difference, borrow)
+}
+
+/// Bring a value below `2 * MODULUS` (with its carry bit) back into range.
+fn reduce_once<F: FieldParams>(value: [u64; LIMBS], carry: bool) -> [u64; LIMBS] {
+    if carry || !less_than(&value, &F::MODULUS) {
+        sub_limbs(&value, &F::MODULUS).0
+    } else {
+        value
+    }
+}
+
+/// `a * b * 2^-256 mod MODULUS` by coarsely integrated operand scanning.
+fn montgomery_mul<F: FieldParams>(a: &[u64; LIMBS], b: &[u64; LIMBS]) -> [u64; LIMBS] {
+    let modulus = &F::MODULUS;
+    let mut t = [0u64; LIMBS + 2];
+    for b_limb in b {
+        let mut carry = 0u128;
+        for j in 0..LIMBS {
+            let total = t[j] as u128 + a[j] as u128 * *b_limb as u128 + carry;
+            t[j] = total as u64;
+            carry = total >> 64;
+        }
+        let total = t[LIMBS] as u128 + carry;
+        t[LIMBS] = total as u64;
+        t[LIMBS + 1] = (total >> 64) as u64;
+
+        let m = t[0].wrapping_mul(F::INV);
+        let mut carry = (t[0] as u128 + m as u128 * modulus[0] as u128) >> 64;
+        for j in 1..LIMBS {
+            let total = t[j] as u128 + m as u128 * modulus[j] as u128 + carry;
+            t[j - 1] = total as u64;
+            carry = total >> 64;
+        }
+        let total = t[LIMBS] as u128 + carry;
+        t[LIMBS - 1] = total as u64;
+        t[LIMBS] = t[LIMBS + 1] + (total >> 64) as u64;
+    }
+
+    reduce_once::<F>([t[0], t[1], t[2], t[3]], t[LIMBS] != 0)
+}
+
+const fn montgomery_inv(modulus_low: u64) -> u64 {
+    // Newton's iteration doubles the number of correct low bits each step.
+    let mut inverse = 1u64;
+    let mut i = 0;
+    while i < 6 {
+        inverse = inverse.wrapping_mul(2u64.wrapping_sub(modulus_low.wrapping_mul(inverse)));
+        i += 1;
+    }
+    inverse.wrapping_neg()
+}
+
+const fn pow2_mod(exponent: usize, modulus: &[u64; LIMBS]) -> [u64; LIMBS] {
+    let mut value = [1u64, 0, 0, 0];
+    let mut i = 0;
+    while i < exponent {
+        let (doubled, carry) = add_limbs(&value, &value);
+        value = if carry || !less_than(&doubled, modulus) { sub_limbs(&doubled, modulus).0 } else { doubled };
+        i += 1;
+    }
+    value
+}
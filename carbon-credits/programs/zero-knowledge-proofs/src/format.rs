@@ -0,0 +1,172 @@
+//! The header shared by every file this crate writes. It records what the file holds and over which
+//! field, and a checksum of the body, so a stale or corrupt file is rejected on load instead of
+//! decoding into garbage.
+//!
+//! Layout, integers little-endian:
+//!
+//! | bytes | content                                              |
+//! |-------|------------------------------------------------------|
+//! | 4     | magic, one per kind of file                          |
+//! | 4     | format version                                       |
+//! | 32    | field modulus                                        |
+//! | 8     | number of constraints                                |
+//! | 8     | number of variables                                  |
+//! | 32    | SHA-256 of the body                                  |
+//! | 8     | body length                                          |
+
+use std::fs;
+use sha2::{Digest, Sha256};
+use crate::error::{Result, ZkError};
+use crate::field::{Bls12_381Fr, Bn254Fr, FieldParams, Goldilocks};
+
+/// Version written into, and required of, every file.
+pub const FORMAT_VERSION: u32 = 1;
+
+pub const HEADER_LEN: usize = 4 + 4 + 32 + 8 + 8 + 32 + 8;
+
+pub const R1CS_MAGIC: [u8; 4] = *b"ZKR1";
+pub const PROOF_MAGIC: [u8; 4] = *b"ZKPF";
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Header {
+    pub magic: [u8; 4],
+    pub version: u32,
+    /// Little-endian modulus of the field the contents are over.
+    pub field: [u8; 32],
+    /// Constraints of a constraint system; zero for other files.
+    pub num_constraints: u64,
+    /// Variables of a constraint system or proving key, including the constant one; public inputs of
+    /// a proof or verifying key; zero for other files.
+    pub num_variables: u64,
+    pub checksum: [u8; 32],
+    pub body_len: u64,
+}
+
+impl Header {
+    /// Header of a file over `F`. The checksum and body length are filled in by `write_file`.
+    pub fn new<F: FieldParams>(magic: [u8; 4], num_constraints: usize, num_variables: usize) -> Self {
+        Header {
+            magic,
+            version: FORMAT_VERSION,
+            field: modulus_le::<F>(),
+            num_constraints: num_constraints as u64,
+            num_variables: num_variables as u64,
+            checksum: [0; 32],
+            body_len: 0,
+        }
+    }
+
+    pub fn to_bytes(&self) -> [u8; HEADER_LEN] {
+        let mut bytes = [0u8; HEADER_LEN];
+        bytes[..4].copy_from_slice(&self.magic);
+        bytes[4..8].copy_from_slice(&self.version.to_le_bytes());
+        bytes[8..40].copy_from_slice(&self.field);
+        bytes[40..48].copy_from_slice(&self.num_constraints.to_le_bytes());
+        bytes[48..56].copy_from_slice(&self.num_variables.to_le_bytes());
+        bytes[56..88].copy_from_slice(&self.checksum);
+        bytes[88..].copy_from_slice(&self.body_len.to_le_bytes());
+        bytes
+    }
+
+    /// Parse the header at the start of `bytes`. Fails with `ZkError::UnsupportedVersion` for any
+    /// version but `FORMAT_VERSION`.
+    pub fn parse(bytes: &[u8]) -> Result<Self> {
+        if bytes.len() < HEADER_LEN {
+            return Err(ZkError::Deserialization(format!("{} bytes is too short for a header", bytes.len())));
+        }
+        let u64_at = |offset: usize| u64::from_le_bytes(bytes[offset..offset + 8].try_into().expect("eight bytes"));
+        let header = Header {
+            magic: bytes[..4].try_into().expect("four bytes"),
+            version: u32::from_le_bytes(bytes[4..8].try_into().expect("four bytes")),
+            field: bytes[8..40].try_into().expect("32 bytes"),
+            num_constraints: u64_at(40),
+            num_variables: u64_at(48),
+            checksum: bytes[56..88].try_into().expect("32 bytes"),
+            body_len: u64_at(88),
+        };
+        if header.version != FORMAT_VERSION {
+            return Err(ZkError::UnsupportedVersion { found: header.version, supported: FORMAT_VERSION });
+        }
+        Ok(header)
+    }
+
+    /// Fails with `ZkError::ModulusMismatch` unless the file is over `F`.
+    pub fn check_field<F: FieldParams>(&self) -> Result<()> {
+        if self.field != modulus_le::<F>() {
+            return Err(ZkError::ModulusMismatch { expected: F::NAME.to_string(), found: field_name(&self.field) });
+        }
+        Ok(())
+    }
+
+    /// Fails with `ZkError::Deserialization` unless the decoded body has the counts the header promised.
+    pub fn check_counts(&self, num_constraints: usize, num_variables: usize) -> Result<()> {
+        if (self.num_constraints, self.num_variables) != (num_constraints as u64, num_variables as u64) {
+            return Err(ZkError::Deserialization(format!(
+                "header promises {} constraints and {} variables, body has {num_constraints} and {num_variables}",
+                self.num_constraints, self.num_variables
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Write `header` and `body` to `filename`, filling in the checksum and length of the body.
+pub fn write_file(filename: &str, mut header: Header, body: &[u8]) -> Result<()> {
+    header.checksum = Sha256::digest(body).into();
+    header.body_len = body.len() as u64;
+    let mut bytes = Vec::with_capacity(HEADER_LEN + body.len());
+    bytes.extend_from_slice(&header.to_bytes());
+    bytes.extend_from_slice(body);
+    Ok(fs::write(filename, bytes)?)
+}
+
+/// Read a file written by `write_file` with the same `magic`, checking its version, length and
+/// checksum. The field and counts are left to the caller, who knows what to expect.
+pub fn read_file(filename: &str, magic: [u8; 4]) -> Result<(Header, Vec<u8>)> {
+    let mut bytes = fs::read(filename)?;
+    let header = Header::parse(&bytes)?;
+    if header.magic != magic {
+        return Err(ZkError::Deserialization(format!(
+            "{filename} is a {} file, expected {}",
+            String::from_utf8_lossy(&header.magic),
+            String::from_utf8_lossy(&magic)
+        )));
+    }
+    let body = bytes.split_off(HEADER_LEN);
+    if body.len() as u64 != header.body_len {
+        return Err(ZkError::Deserialization(format!("expected a {} byte body, found {}", header.body_len, body.len())));
+    }
+    if <[u8; 32]>::from(Sha256::digest(&body)) != header.checksum {
+        return Err(ZkError::ChecksumMismatch);
+    }
+    Ok((header, body))
+}
+
+/// The header of any file written by `write_file`, without reading or checking the body.
+pub fn read_header(filename: &str) -> Result<Header> {
+    let bytes = fs::read(filename)?;
+    Header::parse(&bytes)
+}
+
+pub(crate) fn modulus_le<F: FieldParams>() -> [u8; 32] {
+    let mut bytes = [0u8; 32];
+    for (chunk, limb) in bytes.chunks_exact_mut(8).zip(F::MODULUS) {
+        chunk.copy_from_slice(&limb.to_le_bytes());
+    }
+    bytes
+}
+
+/// Name of a known field, or the hex modulus of an unknown one.
+pub fn field_name(modulus: &[u8; 32]) -> String {
+    [
+        (modulus_le::<Bn254Fr>(), Bn254Fr::NAME),
+        (modulus_le::<Bls12_381Fr>(), Bls12_381Fr::NAME),
+        (modulus_le::<Goldilocks>(), Goldilocks::NAME),
+    ]
+    .iter()
+    .find(|(known, _)| known == modulus)
+    .map_or_else(
+        || format!("0x{}", modulus.iter().rev().map(|byte| format!("{byte:02x}")).collect::<String>()),
+        |(_, name)| name.to_string(),
+    )
+}
@@ -0,0 +1,139 @@
+//! Reusable sub-circuits on top of `Circuit`: booleans, bit decomposition, range checks, comparisons,
+//! equality and selection. Boolean results are wires holding 0 or 1.
+//!
+//! Comparisons take a bit width and assume both operands already fit in it. Values that come from
+//! private inputs should go through `range_check` first, otherwise a prover can pick field elements
+//! that wrap around the modulus. Bit widths too wide for the field fail with
+//! `ZkError::InvalidParameters`.
+
+use crate::circuit::{Circuit, Wire};
+use crate::error::{Result, ZkError};
+use crate::field::{FieldElement, FieldParams};
+
+/// Largest bit width whose values are all below the modulus, so bit decompositions are unique.
+pub fn capacity<F: FieldParams>() -> usize {
+    FieldElement::<F>::modulus().bits() as usize - 1
+}
+
+/// Constrain `wire` to be 0 or 1: `wire * (wire - 1) = 0`.
+pub fn assert_boolean<F: FieldParams>(cs: &mut Circuit<F>, wire: Wire) {
+    let one = FieldElement::one();
+    cs.enforce(&[(one, wire)], &[(one, wire), (-one, Wire::ONE)], &[]);
+}
+
+/// Constrain a boolean wire to be 1.
+pub fn assert_true<F: FieldParams>(cs: &mut Circuit<F>, wire: Wire) {
+    cs.assert_equal(wire, Wire::ONE);
+}
+
+/// A private boolean input.
+pub fn boolean_input<F: FieldParams>(cs: &mut Circuit<F>, value: bool) -> Wire {
+    let wire = cs.private_input(FieldElement::from_u64(value as u64));
+    assert_boolean(cs, wire);
+    wire
+}
+
+pub fn not<F: FieldParams>(cs: &mut Circuit<F>, a: Wire) -> Wire {
+    cs.sub(Wire::ONE, a)
+}
+
+pub fn and<F: FieldParams>(cs: &mut Circuit<F>, a: Wire, b: Wire) -> Wire {
+    cs.mul(a, b)
+}
+
+/// `a + b - a * b`.
+pub fn or<F: FieldParams>(cs: &mut Circuit<F>, a: Wire, b: Wire) -> Wire {
+    let both = cs.mul(a, b);
+    let one = FieldElement::one();
+    cs.linear_combination(&[(one, a), (one, b), (-one, both)])
+}
+
+/// Little-endian bits of `wire`, constrained to be boolean and to recompose to it. If the value does
+/// not fit in `num_bits` bits the circuit is unsatisfiable.
+pub fn to_bits<F: FieldParams>(cs: &mut Circuit<F>, wire: Wire, num_bits: usize) -> Result<Vec<Wire>> {
+    if num_bits > capacity::<F>() {
+        return Err(ZkError::InvalidParameters(format!("{num_bits} bits do not fit below the {} modulus", F::NAME)));
+    }
+
+    let bytes = cs.value(wire).to_bytes_le();
+    let mut recomposition = Vec::with_capacity(num_bits + 1);
+    let mut power_of_two = FieldElement::one();
+    let bits: Vec<Wire> = (0..num_bits)
+        .map(|i| {
+            let bit = cs.hint(FieldElement::from_u64(((bytes[i / 8] >> (i % 8)) & 1) as u64));
+            assert_boolean(cs, bit);
+            recomposition.push((power_of_two, bit));
+            power_of_two = power_of_two + power_of_two;
+            bit
+        })
+        .collect();
+
+    recomposition.push((-FieldElement::one(), wire));
+    cs.enforce(&recomposition, &[(FieldElement::one(), Wire::ONE)], &[]);
+    Ok(bits)
+}
+
+/// Constrain `wire` to be below `2^num_bits`.
+pub fn range_check<F: FieldParams>(cs: &mut Circuit<F>, wire: Wire, num_bits: usize) -> Result<()> {
+    to_bits(cs, wire, num_bits)?;
+    Ok(())
+}
+
+/// `a < b` for operands below `2^num_bits`. Works on the sign bit of `a - b + 2^num_bits`.
+pub fn less_than<F: FieldParams>(cs: &mut Circuit<F>, a: Wire, b: Wire, num_bits: usize) -> Result<Wire> {
+    if num_bits >= capacity::<F>() {
+        return Err(ZkError::InvalidParameters(format!("{num_bits}-bit comparisons do not fit in the {} field", F::NAME)));
+    }
+
+    let offset = FieldElement::from_u64(2).pow(num_bits as u64);
+    let one = FieldElement::one();
+    let shifted = cs.linear_combination(&[(one, a), (-one, b), (offset, Wire::ONE)]);
+    let bits = to_bits(cs, shifted, num_bits + 1)?;
+    Ok(not(cs, bits[num_bits]))
+}
+
+pub fn less_or_equal<F: FieldParams>(cs: &mut Circuit<F>, a: Wire, b: Wire, num_bits: usize) -> Result<Wire> {
+    let greater = less_than(cs, b, a, num_bits)?;
+    Ok(not(cs, greater))
+}
+
+pub fn greater_than<F: FieldParams>(cs: &mut Circuit<F>, a: Wire, b: Wire, num_bits: usize) -> Result<Wire> {
+    less_than(cs, b, a, num_bits)
+}
+
+pub fn greater_or_equal<F: FieldParams>(cs: &mut Circuit<F>, a: Wire, b: Wire, num_bits: usize) -> Result<Wire> {
+    less_or_equal(cs, b, a, num_bits)
+}
+
+/// 1 if `a` is zero, else 0. The prover supplies `a^-1` (or 0) as a hint; `a * result = 0` and
+/// `result = 1 - a * hint` leave no other assignment.
+pub fn is_zero<F: FieldParams>(cs: &mut Circuit<F>, a: Wire) -> Wire {
+    let value = cs.value(a);
+    let inverse = cs.hint(value.inv_or_zero());
+    let product = cs.mul(a, inverse);
+    let result = not(cs, product);
+
+    let one = FieldElement::one();
+    cs.enforce(&[(one, a)], &[(one, result)], &[]);
+    result
+}
+
+pub fn is_equal<F: FieldParams>(cs: &mut Circuit<F>, a: Wire, b: Wire) -> Wire {
+    let difference = cs.sub(a, b);
+    is_zero(cs, difference)
+}
+
+/// `if_true` when `condition` is 1, `if_false` when it is 0. `condition` must be boolean.
+pub fn select<F: FieldParams>(cs: &mut Circuit<F>, condition: Wire, if_true: Wire, if_false: Wire) -> Wire {
+    let chosen = if cs.value(condition).is_zero() { cs.value(if_false) } else { cs.value(if_true) };
+    let result = cs.hint(chosen);
+
+    // condition * (if_true - if_false) = result - if_false
+    let one = FieldElement::one();
+    cs.enforce(
+        &[(one, condition)],
+        &[(one, if_true), (-one, if_false)],
+        &[(one, result), (-one, if_false)],
+    );
+    result
+}
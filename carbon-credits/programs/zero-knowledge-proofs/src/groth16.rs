@@ -0,0 +1,181 @@
+use ark_bn254::{Bn254, Fr, G1Affine, G2Affine};
+use ark_ff::{BigInteger, PrimeField};
+use ark_groth16::Groth16;
+use ark_relations::r1cs::{
+    ConstraintMatrices, ConstraintSynthesizer, ConstraintSystem, ConstraintSystemRef, LinearCombination,
+    OptimizationGoal, SynthesisError, SynthesisMode, Variable as ArkVariable,
+};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_snark::SNARK;
+use ark_std::rand::{CryptoRng, RngCore};
+use crate::error::{Result, ZkError};
+use crate::field::FieldElement;
+use crate::r1cs::{Variable, R1CS};
+
+pub type ProvingKey = ark_groth16::ProvingKey<Bn254>;
+pub type VerifyingKey = ark_groth16::VerifyingKey<Bn254>;
+pub type Groth16Proof = ark_groth16::Proof<Bn254>;
+
+/// Adapter that lays an `R1CS` out as an arkworks constraint system. Variable 0 is arkworks' own
+/// constant one, the public inputs of the R1CS become instance inputs and the rest stay private
+/// witness values.
+struct R1CSCircuit<'a> {
+    r1cs: &'a R1CS,
+    witness: Option<&'a [FieldElement]>,
+}
+
+impl ConstraintSynthesizer<Fr> for R1CSCircuit<'_> {
+    fn generate_constraints(self, cs: ConstraintSystemRef<Fr>) -> Result<(), SynthesisError> {
+        let mut variables = vec![ArkVariable::One];
+        for index in 1..self.r1cs.variables.len() {
+            let value = || {
+                self.witness
+                    .and_then(|witness| witness.get(index))
+                    .map(to_fr)
+                    .ok_or(SynthesisError::AssignmentMissing)
+            };
+            let variable = if index <= self.r1cs.num_public() {
+                cs.new_input_variable(value)?
+            } else {
+                cs.new_witness_variable(value)?
+            };
+            variables.push(variable);
+        }
+
+        let combine = |terms: &[(Variable, FieldElement)]| {
+            terms.iter().fold(LinearCombination::<Fr>::zero(), |lc, (variable, coeff)| {
+                lc + (to_fr(coeff), variables[variable.index])
+            })
+        };
+
+        for constraint in &self.r1cs.constraints {
+            cs.enforce_constraint(
+                combine(&constraint.left),
+                combine(&constraint.right),
+                combine(&constraint.output),
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Run the circuit-specific Groth16 setup for `r1cs`. The randomness drawn from `rng` is the toxic
+/// waste; it is dropped when this function returns.
+pub fn setup<R: RngCore + CryptoRng>(r1cs: &R1CS, rng: &mut R) -> Result<(ProvingKey, VerifyingKey)> {
+    let circuit = R1CSCircuit { r1cs, witness: None };
+    Ok(Groth16::<Bn254>::circuit_specific_setup(circuit, rng)?)
+}
+
+/// The A, B and C matrices of `r1cs` exactly as `setup` and `prove` lay them out, for building keys
+/// by other means than `setup`.
+pub(crate) fn constraint_matrices(r1cs: &R1CS) -> Result<ConstraintMatrices<Fr>> {
+    let cs = ConstraintSystem::<Fr>::new_ref();
+    cs.set_optimization_goal(OptimizationGoal::Constraints);
+    cs.set_mode(SynthesisMode::Setup);
+    R1CSCircuit { r1cs, witness: None }.generate_constraints(cs.clone())?;
+    cs.finalize();
+    cs.to_matrices().ok_or(ZkError::Synthesis(SynthesisError::MissingCS))
+}
+
+/// Prove that `witness` satisfies `r1cs`. Only the public inputs of `r1cs` are revealed to the
+/// verifier; the proof itself is three group elements regardless of circuit size. Fails with
+/// `ZkError::Unsatisfied` if the witness breaks a constraint.
+pub fn prove<R: RngCore + CryptoRng>(
+    pk: &ProvingKey,
+    r1cs: &R1CS,
+    witness: &[FieldElement],
+    rng: &mut R,
+) -> Result<Groth16Proof> {
+    if witness.len() != r1cs.variables.len() {
+        return Err(ZkError::LengthMismatch { expected: r1cs.variables.len(), actual: witness.len() });
+    }
+
+    // The prover happily produces a proof for a bad witness; catch that here rather than at verification.
+    let cs = ConstraintSystem::<Fr>::new_ref();
+    R1CSCircuit { r1cs, witness: Some(witness) }.generate_constraints(cs.clone())?;
+    if !cs.is_satisfied()? {
+        return Err(ZkError::Unsatisfied);
+    }
+
+    let circuit = R1CSCircuit { r1cs, witness: Some(witness) };
+    Ok(Groth16::<Bn254>::prove(pk, circuit, rng)?)
+}
+
+/// Check `proof` against the verifying key and the public inputs, in the order they were laid out and
+/// without the constant one.
+pub fn verify(vk: &VerifyingKey, public_inputs: &[FieldElement], proof: &Groth16Proof) -> bool {
+    let public_inputs: Vec<Fr> = public_inputs.iter().map(to_fr).collect();
+    Groth16::<Bn254>::verify(vk, &public_inputs, proof).unwrap_or(false)
+}
+
+/// Size of a proof in the encoding taken by the Solana alt_bn128 syscalls: `a || b || c`.
+pub const SOLANA_PROOF_LEN: usize = 64 + 128 + 64;
+
+/// Verifying key laid out for the Solana alt_bn128 syscalls. `ic` holds one point per public input,
+/// plus one for the constant term.
+pub struct SolanaVerifyingKey {
+    pub alpha_g1: [u8; 64],
+    pub beta_g2: [u8; 128],
+    pub gamma_g2: [u8; 128],
+    pub delta_g2: [u8; 128],
+    pub ic: Vec<[u8; 64]>,
+}
+
+/// Big-endian `x || y`. The point at infinity encodes as all zeroes, as in EIP-197.
+pub fn g1_to_be_bytes(point: &G1Affine) -> [u8; 64] {
+    let mut bytes = [0u8; 64];
+    bytes[..32].copy_from_slice(&point.x.into_bigint().to_bytes_be());
+    bytes[32..].copy_from_slice(&point.y.into_bigint().to_bytes_be());
+    bytes
+}
+
+/// EIP-197 order: `x.c1 || x.c0 || y.c1 || y.c0`, each coordinate big-endian.
+pub fn g2_to_be_bytes(point: &G2Affine) -> [u8; 128] {
+    let mut bytes = [0u8; 128];
+    bytes[..32].copy_from_slice(&point.x.c1.into_bigint().to_bytes_be());
+    bytes[32..64].copy_from_slice(&point.x.c0.into_bigint().to_bytes_be());
+    bytes[64..96].copy_from_slice(&point.y.c1.into_bigint().to_bytes_be());
+    bytes[96..].copy_from_slice(&point.y.c0.into_bigint().to_bytes_be());
+    bytes
+}
+
+pub fn proof_to_solana_bytes(proof: &Groth16Proof) -> [u8; SOLANA_PROOF_LEN] {
+    let mut bytes = [0u8; SOLANA_PROOF_LEN];
+    bytes[..64].copy_from_slice(&g1_to_be_bytes(&proof.a));
+    bytes[64..192].copy_from_slice(&g2_to_be_bytes(&proof.b));
+    bytes[192..].copy_from_slice(&g1_to_be_bytes(&proof.c));
+    bytes
+}
+
+pub fn verifying_key_to_solana(vk: &VerifyingKey) -> SolanaVerifyingKey {
+    SolanaVerifyingKey {
+        alpha_g1: g1_to_be_bytes(&vk.alpha_g1),
+        beta_g2: g2_to_be_bytes(&vk.beta_g2),
+        gamma_g2: g2_to_be_bytes(&vk.gamma_g2),
+        delta_g2: g2_to_be_bytes(&vk.delta_g2),
+        ic: vk.gamma_abc_g1.iter().map(g1_to_be_bytes).collect(),
+    }
+}
+
+/// A public input as the 32-byte big-endian scalar the on-chain verifier expects.
+pub fn public_input_to_be_bytes(value: &FieldElement) -> [u8; 32] {
+    value.to_bytes_be()
+}
+
+/// Compressed arkworks encoding, as stored in proof and key files.
+pub fn to_bytes<T: CanonicalSerialize>(value: &T) -> Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+    value.serialize_compressed(&mut bytes).map_err(|error| ZkError::Serialization(error.to_string()))?;
+    Ok(bytes)
+}
+
+/// Decode and validate a compressed arkworks encoding; points must be on the curve and in the subgroup.
+pub fn from_bytes<T: CanonicalDeserialize>(bytes: &[u8]) -> Result<T> {
+    T::deserialize_compressed(bytes).map_err(|error| ZkError::Deserialization(error.to_string()))
+}
+
+/// `FieldElement` and `Fr` are the same field, so this is an exact conversion.
+pub fn to_fr(value: &FieldElement) -> Fr {
+    Fr::from_le_bytes_mod_order(&value.to_bytes_le())
+}
@@ -0,0 +1,446 @@
+//! The iden3 binary formats circom and snarkjs use: `.r1cs` for constraint systems, `.wtns` for
+//! witnesses and `.ptau` for Powers of Tau transcripts. All are a magic, a version and a list of
+//! `(type u32, size u64, data)` sections, with integers little-endian and field elements in canonical
+//! little-endian form over `n8` bytes. Curve points in `.ptau` files are the exception: their
+//! coordinates are in Montgomery form, as snarkjs keeps them in memory.
+//!
+//! circom numbers wires as the constant one, the public outputs, the public inputs, the private
+//! inputs and then the internal signals, which is the layout `R1CS` already uses with the outputs
+//! and inputs together as its public inputs.
+
+use std::fs::{self, File};
+use std::io::{Read, Seek, SeekFrom};
+use ark_bn254::{Fq, Fq2, G1Affine, G2Affine};
+use ark_ec::AffineRepr;
+use ark_ff::{BigInt, BigInteger, PrimeField};
+use crate::error::{Result, ZkError};
+use crate::field::{FieldElement, FieldParams};
+use crate::format::{field_name, modulus_le};
+use crate::r1cs::R1CS;
+use crate::setup::PowersOfTau;
+
+const R1CS_VERSION: u32 = 1;
+const R1CS_HEADER: u32 = 1;
+const R1CS_CONSTRAINTS: u32 = 2;
+const R1CS_WIRE_TO_LABEL: u32 = 3;
+
+const WTNS_VERSION: u32 = 2;
+const WTNS_HEADER: u32 = 1;
+const WTNS_VALUES: u32 = 2;
+
+const PTAU_VERSION: u32 = 1;
+const PTAU_HEADER: u32 = 1;
+const PTAU_TAU_G1: u32 = 2;
+const PTAU_TAU_G2: u32 = 3;
+const PTAU_ALPHA_TAU_G1: u32 = 4;
+const PTAU_BETA_TAU_G1: u32 = 5;
+const PTAU_BETA_G2: u32 = 6;
+const PTAU_CONTRIBUTIONS: u32 = 7;
+
+const G1_SIZE: usize = 64;
+const G2_SIZE: usize = 128;
+
+/// Sizes and public/private split of an iden3 constraint system.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct R1csHeader {
+    pub num_wires: u32,
+    pub num_public_outputs: u32,
+    pub num_public_inputs: u32,
+    pub num_private_inputs: u32,
+    pub num_labels: u64,
+    pub num_constraints: u32,
+}
+
+/// Write `r1cs` as an iden3 `.r1cs` file. All of its public inputs are written as circom public
+/// inputs and every wire is its own label.
+pub fn write_r1cs<F: FieldParams>(r1cs: &R1CS<F>, filename: &str) -> Result<()> {
+    let n8 = field_size::<F>();
+    let num_wires = u32_count(r1cs.variables.len())?;
+
+    let mut header = Vec::new();
+    header.extend_from_slice(&(n8 as u32).to_le_bytes());
+    header.extend_from_slice(&modulus_le::<F>()[..n8]);
+    for count in [num_wires, 0, u32_count(r1cs.num_public())?, 0] {
+        header.extend_from_slice(&count.to_le_bytes());
+    }
+    header.extend_from_slice(&(num_wires as u64).to_le_bytes());
+    header.extend_from_slice(&u32_count(r1cs.constraints.len())?.to_le_bytes());
+
+    let mut constraints = Vec::new();
+    for constraint in &r1cs.constraints {
+        for terms in [&constraint.left, &constraint.right, &constraint.output] {
+            // circom expects one term per wire, in increasing wire order.
+            let mut merged: Vec<(usize, FieldElement<F>)> = Vec::new();
+            for (variable, coeff) in terms {
+                match merged.iter_mut().find(|(index, _)| *index == variable.index) {
+                    Some((_, sum)) => *sum += coeff,
+                    None => merged.push((variable.index, *coeff)),
+                }
+            }
+            merged.retain(|(_, coeff)| !coeff.is_zero());
+            merged.sort_by_key(|(index, _)| *index);
+
+            constraints.extend_from_slice(&u32_count(merged.len())?.to_le_bytes());
+            for (index, coeff) in merged {
+                constraints.extend_from_slice(&(index as u32).to_le_bytes());
+                constraints.extend_from_slice(&coeff.to_bytes_le()[..n8]);
+            }
+        }
+    }
+
+    let labels: Vec<u8> = (0..num_wires as u64).flat_map(u64::to_le_bytes).collect();
+    let sections = [(R1CS_HEADER, header), (R1CS_CONSTRAINTS, constraints), (R1CS_WIRE_TO_LABEL, labels)];
+    Ok(fs::write(filename, encode(b"r1cs", R1CS_VERSION, &sections))?)
+}
+
+/// Read an iden3 `.r1cs` file. Every variable is zero; assign a witness, e.g. from `read_wtns`,
+/// before proving. Fails with `ZkError::ModulusMismatch` if the file is over another field.
+pub fn read_r1cs<F: FieldParams>(filename: &str) -> Result<(R1csHeader, R1CS<F>)> {
+    let bytes = fs::read(filename)?;
+    let sections = decode(&bytes, b"r1cs", R1CS_VERSION)?;
+
+    let mut reader = Reader::new(section(&sections, R1CS_HEADER)?);
+    let n8 = read_field_size::<F>(&mut reader)?;
+    let header = R1csHeader {
+        num_wires: reader.u32()?,
+        num_public_outputs: reader.u32()?,
+        num_public_inputs: reader.u32()?,
+        num_private_inputs: reader.u32()?,
+        num_labels: reader.u64()?,
+        num_constraints: reader.u32()?,
+    };
+    let num_public = header.num_public_outputs as usize + header.num_public_inputs as usize;
+    if header.num_wires == 0 || num_public >= header.num_wires as usize {
+        return Err(ZkError::Deserialization(format!("{num_public} public signals but {} wires", header.num_wires)));
+    }
+
+    let mut r1cs = R1CS::new();
+    for _ in 0..num_public {
+        r1cs.add_public_input(FieldElement::zero())?;
+    }
+    for _ in num_public + 1..header.num_wires as usize {
+        r1cs.add_variable(FieldElement::zero());
+    }
+
+    let mut reader = Reader::new(section(&sections, R1CS_CONSTRAINTS)?);
+    for _ in 0..header.num_constraints {
+        let mut terms = || -> Result<Vec<(usize, FieldElement<F>)>> {
+            (0..reader.u32()?).map(|_| Ok((reader.u32()? as usize, reader.element(n8)?))).collect()
+        };
+        let (left, right, output) = (terms()?, terms()?, terms()?);
+        r1cs.add_constraint(&left, &right, &output)?;
+    }
+    reader.finish()?;
+    Ok((header, r1cs))
+}
+
+/// A circom-compiled circuit with its witness, ready for `groth16::setup` and `groth16::prove`. The
+/// witness comes from a `.wtns` file, as written by circom's witness calculator or
+/// `snarkjs wtns calculate`; this crate does not run the `.wasm` calculator itself. Fails with
+/// `ZkError::Unsatisfied` if the witness does not satisfy the constraints.
+pub fn read_circuit<F: FieldParams>(r1cs_filename: &str, wtns_filename: &str) -> Result<R1CS<F>> {
+    let (_, mut r1cs) = read_r1cs(r1cs_filename)?;
+    let witness = read_wtns(wtns_filename)?;
+    r1cs.assign_witness(&witness)?;
+    if witness[0] != FieldElement::one() {
+        return Err(ZkError::Deserialization("the first witness value must be one".into()));
+    }
+    if !r1cs.verify_witness(&witness) {
+        return Err(ZkError::Unsatisfied);
+    }
+    Ok(r1cs)
+}
+
+/// Write a full witness, constant one included, as an iden3 `.wtns` file.
+pub fn write_wtns<F: FieldParams>(witness: &[FieldElement<F>], filename: &str) -> Result<()> {
+    let n8 = field_size::<F>();
+    let mut header = Vec::new();
+    header.extend_from_slice(&(n8 as u32).to_le_bytes());
+    header.extend_from_slice(&modulus_le::<F>()[..n8]);
+    header.extend_from_slice(&u32_count(witness.len())?.to_le_bytes());
+
+    let values: Vec<u8> = witness.iter().flat_map(|value| value.to_bytes_le()[..n8].to_vec()).collect();
+    Ok(fs::write(filename, encode(b"wtns", WTNS_VERSION, &[(WTNS_HEADER, header), (WTNS_VALUES, values)]))?)
+}
+
+/// Read an iden3 `.wtns` file. Fails with `ZkError::ModulusMismatch` if it is over another field.
+pub fn read_wtns<F: FieldParams>(filename: &str) -> Result<Vec<FieldElement<F>>> {
+    let bytes = fs::read(filename)?;
+    let sections = decode(&bytes, b"wtns", WTNS_VERSION)?;
+
+    let mut reader = Reader::new(section(&sections, WTNS_HEADER)?);
+    let n8 = read_field_size::<F>(&mut reader)?;
+    let count = reader.u32()?;
+
+    let mut reader = Reader::new(section(&sections, WTNS_VALUES)?);
+    let witness = (0..count).map(|_| reader.element(n8)).collect::<Result<Vec<_>>>()?;
+    reader.finish()?;
+    Ok(witness)
+}
+
+/// Read the powers for domains of up to `size` points from a snarkjs `.ptau` file, such as the
+/// Hermez ceremony's `powersOfTau28_hez_final_*.ptau`. Only the sections the Groth16 keys need are
+/// read, and only as far as `size` requires, so large files are cheap. Fails with
+/// `ZkError::DomainSize` if the file was made for fewer points. The result is not checked; that is
+/// `PowersOfTau::verify`.
+pub fn read_ptau(filename: &str, size: usize) -> Result<PowersOfTau> {
+    if !size.is_power_of_two() {
+        return Err(ZkError::InvalidParameters(format!("{size} is not a power of two")));
+    }
+    let mut file = File::open(filename)?;
+    let sections = section_table(&mut file, b"ptau", PTAU_VERSION)?;
+
+    let header = read_section(&mut file, &sections, PTAU_HEADER, None)?;
+    let mut reader = Reader::new(&header);
+    let n8 = reader.u32()? as usize;
+    if n8 != 32 || reader.take(n8)? != Fq::MODULUS.to_bytes_le() {
+        return Err(ZkError::Deserialization("not a Powers of Tau over BN254".into()));
+    }
+    let power = reader.u32()?;
+    let available = 1usize.checked_shl(power).unwrap_or(usize::MAX);
+    if size > available {
+        return Err(ZkError::DomainSize { required: size, available });
+    }
+
+    let mut points = |kind: u32, count: usize, point_size: usize| -> Result<Vec<u8>> {
+        read_section(&mut file, &sections, kind, Some(count * point_size))
+    };
+    let g1 = |bytes: Vec<u8>| bytes.chunks_exact(G1_SIZE).map(read_g1).collect::<Result<Vec<_>>>();
+    let g2 = |bytes: Vec<u8>| bytes.chunks_exact(G2_SIZE).map(read_g2).collect::<Result<Vec<_>>>();
+    Ok(PowersOfTau {
+        tau_g1: g1(points(PTAU_TAU_G1, 2 * size - 1, G1_SIZE)?)?,
+        tau_g2: g2(points(PTAU_TAU_G2, size, G2_SIZE)?)?,
+        alpha_tau_g1: g1(points(PTAU_ALPHA_TAU_G1, size, G1_SIZE)?)?,
+        beta_tau_g1: g1(points(PTAU_BETA_TAU_G1, size, G1_SIZE)?)?,
+        beta_g2: read_g2(&points(PTAU_BETA_G2, 1, G2_SIZE)?)?,
+    })
+}
+
+/// Write `powers` as a snarkjs `.ptau` file with no recorded contributions.
+pub fn write_ptau(powers: &PowersOfTau, filename: &str) -> Result<()> {
+    let mut header = Vec::new();
+    header.extend_from_slice(&32u32.to_le_bytes());
+    header.extend_from_slice(&Fq::MODULUS.to_bytes_le());
+    let power = powers.size().trailing_zeros();
+    header.extend_from_slice(&power.to_le_bytes());
+    header.extend_from_slice(&power.to_le_bytes());
+
+    let g1 = |points: &[G1Affine]| points.iter().flat_map(write_g1).collect::<Vec<u8>>();
+    let g2 = |points: &[G2Affine]| points.iter().flat_map(write_g2).collect::<Vec<u8>>();
+    let sections = [
+        (PTAU_HEADER, header),
+        (PTAU_TAU_G1, g1(&powers.tau_g1)),
+        (PTAU_TAU_G2, g2(&powers.tau_g2)),
+        (PTAU_ALPHA_TAU_G1, g1(&powers.alpha_tau_g1)),
+        (PTAU_BETA_TAU_G1, g1(&powers.beta_tau_g1)),
+        (PTAU_BETA_G2, g2(&[powers.beta_g2])),
+        (PTAU_CONTRIBUTIONS, 0u32.to_le_bytes().to_vec()),
+    ];
+    Ok(fs::write(filename, encode(b"ptau", PTAU_VERSION, &sections))?)
+}
+
+/// Bytes per element: the modulus rounded up to whole 64-bit words, as circom does.
+fn field_size<F: FieldParams>() -> usize {
+    8 * (F::MODULUS.iter().rposition(|limb| *limb != 0).unwrap_or(0) + 1)
+}
+
+fn read_field_size<F: FieldParams>(reader: &mut Reader) -> Result<usize> {
+    let n8 = reader.u32()? as usize;
+    if n8 == 0 || n8 > 32 {
+        return Err(ZkError::Deserialization(format!("unsupported element size of {n8} bytes")));
+    }
+    let mut modulus = [0u8; 32];
+    modulus[..n8].copy_from_slice(reader.take(n8)?);
+    if modulus != modulus_le::<F>() {
+        return Err(ZkError::ModulusMismatch { expected: F::NAME.to_string(), found: field_name(&modulus) });
+    }
+    Ok(n8)
+}
+
+fn u32_count(count: usize) -> Result<u32> {
+    u32::try_from(count).map_err(|_| ZkError::Serialization(format!("{count} does not fit the iden3 format")))
+}
+
+fn encode(magic: &[u8; 4], version: u32, sections: &[(u32, Vec<u8>)]) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    bytes.extend_from_slice(magic);
+    bytes.extend_from_slice(&version.to_le_bytes());
+    bytes.extend_from_slice(&(sections.len() as u32).to_le_bytes());
+    for (kind, data) in sections {
+        bytes.extend_from_slice(&kind.to_le_bytes());
+        bytes.extend_from_slice(&(data.len() as u64).to_le_bytes());
+        bytes.extend_from_slice(data);
+    }
+    bytes
+}
+
+/// The `(type, data)` sections of a file, in file order.
+fn decode<'a>(bytes: &'a [u8], magic: &[u8; 4], version: u32) -> Result<Vec<(u32, &'a [u8])>> {
+    let mut reader = Reader::new(bytes);
+    if reader.take(4)? != magic {
+        return Err(ZkError::Deserialization(format!("not a {} file", String::from_utf8_lossy(magic))));
+    }
+    let found = reader.u32()?;
+    if found != version {
+        return Err(ZkError::UnsupportedVersion { found, supported: version });
+    }
+    let sections = (0..reader.u32()?)
+        .map(|_| {
+            let kind = reader.u32()?;
+            let size = usize::try_from(reader.u64()?).map_err(|_| ZkError::Deserialization("section too large".into()))?;
+            Ok((kind, reader.take(size)?))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    reader.finish()?;
+    Ok(sections)
+}
+
+/// Like `decode`, but only records where each section starts and how long it is, so a large file
+/// need not be read whole.
+fn section_table(file: &mut File, magic: &[u8; 4], version: u32) -> Result<Vec<(u32, u64, u64)>> {
+    let mut start = [0u8; 12];
+    file.read_exact(&mut start)?;
+    let mut reader = Reader::new(&start);
+    if reader.take(4)? != magic {
+        return Err(ZkError::Deserialization(format!("not a {} file", String::from_utf8_lossy(magic))));
+    }
+    let found = reader.u32()?;
+    if found != version {
+        return Err(ZkError::UnsupportedVersion { found, supported: version });
+    }
+
+    let file_len = file.metadata()?.len();
+    (0..reader.u32()?)
+        .map(|_| {
+            let mut section = [0u8; 12];
+            file.read_exact(&mut section)?;
+            let mut reader = Reader::new(&section);
+            let (kind, size) = (reader.u32()?, reader.u64()?);
+            let offset = file.stream_position()?;
+            if offset.checked_add(size).is_none_or(|end| end > file_len) {
+                return Err(ZkError::Deserialization("unexpected end of file".into()));
+            }
+            file.seek(SeekFrom::Current(size as i64))?;
+            Ok((kind, offset, size))
+        })
+        .collect()
+}
+
+/// The first `len` bytes of a section, or all of it.
+fn read_section(file: &mut File, sections: &[(u32, u64, u64)], kind: u32, len: Option<usize>) -> Result<Vec<u8>> {
+    let (_, offset, size) = sections
+        .iter()
+        .find(|(found, _, _)| *found == kind)
+        .ok_or_else(|| ZkError::Deserialization(format!("missing section {kind}")))?;
+    let len = len.unwrap_or(*size as usize);
+    if len as u64 > *size {
+        return Err(ZkError::Deserialization(format!("section {kind} is too short")));
+    }
+    let mut bytes = vec![0u8; len];
+    file.seek(SeekFrom::Start(*offset))?;
+    file.read_exact(&mut bytes)?;
+    Ok(bytes)
+}
+
+/// A base field element in Montgomery form.
+fn read_fq(bytes: &[u8]) -> Result<Fq> {
+    let limbs = std::array::from_fn(|i| u64::from_le_bytes(bytes[8 * i..8 * i + 8].try_into().expect("eight bytes")));
+    let montgomery = BigInt::new(limbs);
+    if montgomery >= Fq::MODULUS {
+        return Err(ZkError::Deserialization("coordinate is not reduced modulo the base field".into()));
+    }
+    Ok(Fq::new_unchecked(montgomery))
+}
+
+fn write_fq(value: &Fq) -> Vec<u8> {
+    value.0.0.iter().flat_map(|limb| limb.to_le_bytes()).collect()
+}
+
+/// An `(x, y)` point, all zeros for the point at infinity, checked to be in the prime-order group.
+fn read_g1(bytes: &[u8]) -> Result<G1Affine> {
+    if bytes.iter().all(|byte| *byte == 0) {
+        return Ok(G1Affine::zero());
+    }
+    let point = G1Affine::new_unchecked(read_fq(&bytes[..32])?, read_fq(&bytes[32..])?);
+    if !point.is_on_curve() || !point.is_in_correct_subgroup_assuming_on_curve() {
+        return Err(ZkError::Deserialization("G1 point is not in the group".into()));
+    }
+    Ok(point)
+}
+
+fn write_g1(point: &G1Affine) -> Vec<u8> {
+    match point.xy() {
+        Some((x, y)) => [write_fq(x), write_fq(y)].concat(),
+        None => vec![0; G1_SIZE],
+    }
+}
+
+/// An `(x.c0, x.c1, y.c0, y.c1)` point, all zeros for the point at infinity.
+fn read_g2(bytes: &[u8]) -> Result<G2Affine> {
+    if bytes.iter().all(|byte| *byte == 0) {
+        return Ok(G2Affine::zero());
+    }
+    let x = Fq2::new(read_fq(&bytes[..32])?, read_fq(&bytes[32..64])?);
+    let y = Fq2::new(read_fq(&bytes[64..96])?, read_fq(&bytes[96..])?);
+    let point = G2Affine::new_unchecked(x, y);
+    if !point.is_on_curve() || !point.is_in_correct_subgroup_assuming_on_curve() {
+        return Err(ZkError::Deserialization("G2 point is not in the group".into()));
+    }
+    Ok(point)
+}
+
+fn write_g2(point: &G2Affine) -> Vec<u8> {
+    match point.xy() {
+        Some((x, y)) => [write_fq(&x.c0), write_fq(&x.c1), write_fq(&y.c0), write_fq(&y.c1)].concat(),
+        None => vec![0; G2_SIZE],
+    }
+}
+
+fn section<'a>(sections: &[(u32, &'a [u8])], kind: u32) -> Result<&'a [u8]> {
+    sections
+        .iter()
+        .find(|(found, _)| *found == kind)
+        .map(|(_, data)| *data)
+        .ok_or_else(|| ZkError::Deserialization(format!("missing section {kind}")))
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Reader { bytes }
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        if self.bytes.len() < len {
+            return Err(ZkError::Deserialization("unexpected end of file".into()));
+        }
+        let (head, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+        Ok(head)
+    }
+
+    fn u32(&mut self) -> Result<u32> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().expect("four bytes")))
+    }
+
+    fn u64(&mut self) -> Result<u64> {
+        Ok(u64::from_le_bytes(self.take(8)?.try_into().expect("eight bytes")))
+    }
+
+    fn element<F: FieldParams>(&mut self, n8: usize) -> Result<FieldElement<F>> {
+        let mut bytes = [0u8; 32];
+        bytes[..n8].copy_from_slice(self.take(n8)?);
+        FieldElement::from_bytes_le(&bytes)
+            .ok_or_else(|| ZkError::Deserialization(format!("value is not reduced modulo the {} modulus", F::NAME)))
+    }
+
+    fn finish(&self) -> Result<()> {
+        if !self.bytes.is_empty() {
+            return Err(ZkError::Deserialization(format!("{} trailing bytes", self.bytes.len())));
+        }
+        Ok(())
+    }
+}
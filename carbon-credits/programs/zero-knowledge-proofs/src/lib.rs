@@ -1,79 +1,127 @@
-mod circuit;
-mod r1cs;
-mod merkle;
-mod qap;
-mod field;
-mod proof;
-use num_bigint::{ToBigInt};
-use circuit::Circuit;
-use crate::field::FieldElement;
+pub mod error;
+pub mod format;
+pub mod circuit;
+pub mod r1cs;
+pub mod merkle;
+pub mod qap;
+pub mod poly;
+pub mod field;
+pub mod groth16;
+pub mod proof;
+pub mod setup;
+pub mod gadgets;
+pub mod iden3;
+pub mod poseidon;
+pub mod sparse_merkle;
+use rand::rngs::OsRng;
+use crate::error::Result;
+use crate::field::{Bn254Fr, FieldElement};
+use crate::groth16::ProvingKey;
+use crate::circuit::Circuit;
+use crate::sparse_merkle::{key_halves, Key};
 
-pub fn eligibility_proof(
-    net_emissions: u32,
-    total_credits_returned: u32,
-    total_borrowed: u32,
-    debt: u32,
-    carbon_credits: u32,
-    reputation: u32,
-    org2_carbon_credits: u32,
-    org2_debt: u32,
-) -> (i32, Vec<u8>) {
-    // Convert u32 inputs to FieldElement
-    let net_emissions_fe = FieldElement::new(net_emissions.to_bigint().unwrap());
-    let total_credits_returned_fe = FieldElement::new(total_credits_returned.to_bigint().unwrap());
-    let total_borrowed_fe = FieldElement::new(total_borrowed.to_bigint().unwrap());
-    let debt_fe = FieldElement::new(debt.to_bigint().unwrap());
-    let carbon_credits_fe = FieldElement::new(carbon_credits.to_bigint().unwrap());
-    let reputation_fe = FieldElement::new(reputation.to_bigint().unwrap());
-    let org2_carbon_credits_fe = FieldElement::new(org2_carbon_credits.to_bigint().unwrap());
-    let org2_debt_fe = FieldElement::new(org2_debt.to_bigint().unwrap());
-
-    // Create a new circuit
-    let mut circuit = Circuit::new();
+/// Number of public inputs of the eligibility circuit: the score threshold, the borrower commitment
+/// and the record commitment, in that order. The balances behind the score stay in the private witness.
+pub const ELIGIBILITY_PUBLIC_INPUTS: usize = 3;
 
-    // Add inputs to the circuit
-    let net_emissions_idx = circuit.add_input(net_emissions_fe.clone());
-    let total_credits_returned_idx = circuit.add_input(total_credits_returned_fe.clone());
-    let total_borrowed_idx = circuit.add_input(total_borrowed_fe.clone());
-    let debt_idx = circuit.add_input(debt_fe.clone());
-    let carbon_credits_idx = circuit.add_input(carbon_credits_fe.clone());
-    let reputation_idx = circuit.add_input(reputation_fe.clone());
-    let org2_carbon_credits_idx = circuit.add_input(org2_carbon_credits_fe.clone());
-    let org2_debt_idx = circuit.add_input(org2_debt_fe.clone());
+/// Seed of the development keys for the eligibility circuit. It is committed, so anyone can rederive
+/// the toxic waste and forge proofs under these keys.
+#[cfg(feature = "dev-keys")]
+const ELIGIBILITY_DEVELOPMENT_SEED: [u8; 32] = *b"carbon-credits/eligibility/dev-1";
 
-    // Step 1: Multiply reputation and carbon_credits
-    let reputation_times_credits = circuit.get_input(reputation_idx).unwrap().get_value() * 
-                                   circuit.get_input(carbon_credits_idx).unwrap().get_value();
-    let multiply_result_idx = circuit.add_input(FieldElement::new(reputation_times_credits.clone()));
+/// Circuit for `reputation * carbon_credits + total_credits_returned >= threshold`, bound to one
+/// borrower. The public inputs are the threshold, `borrower_commitment(borrower)` and
+/// `record_commitment` of the balances; the balances themselves are private. The `carbon_credit`
+/// program computes both commitments from the signer and its on-chain record, so a proof cannot be
+/// replayed for another borrower or made from balances the borrower does not have. The balances are
+/// range-checked to 64 bits, so the score cannot wrap around the field.
+pub fn eligibility_circuit(
+    total_credits_returned: u64,
+    carbon_credits: u64,
+    reputation: u64,
+    threshold: u64,
+    borrower: &Key,
+) -> Result<Circuit> {
+    let mut cs = Circuit::new();
+    let threshold = cs.public_input(FieldElement::from_u64(threshold));
+    let [high, low] = key_halves(borrower);
+    let (high, low) = (cs.private_input(high), cs.private_input(low));
+    let commitment = poseidon::hash_gadget(&mut cs, &[high, low]);
+    cs.expose(commitment);
 
-    // Add multiplication gate
-    circuit.add_gate(circuit::Gate::Mul(reputation_idx, carbon_credits_idx, multiply_result_idx));
+    let total_credits_returned = cs.private_input(FieldElement::from_u64(total_credits_returned));
+    let carbon_credits = cs.private_input(FieldElement::from_u64(carbon_credits));
+    let reputation = cs.private_input(FieldElement::from_u64(reputation));
+    let record = poseidon::hash_gadget(&mut cs, &[total_credits_returned, carbon_credits, reputation]);
+    cs.expose(record);
+    for input in [threshold, total_credits_returned, carbon_credits, reputation] {
+        gadgets::range_check(&mut cs, input, 64)?;
+    }
 
-    // Step 2: Add total_credits_returned to the multiplication result
-    let score_value = circuit.get_input(multiply_result_idx).unwrap() + 
-                      circuit.get_input(total_credits_returned_idx).unwrap();
-    let final_score_idx = circuit.add_input(score_value.clone());
+    // The score is below 2^128 + 2^64, so both sides of the comparison fit in 129 bits.
+    let weighted_credits = cs.mul(reputation, carbon_credits);
+    let score = cs.add(weighted_credits, total_credits_returned);
+    let eligible = gadgets::greater_or_equal(&mut cs, score, threshold, 129)?;
+    gadgets::assert_true(&mut cs, eligible);
+    Ok(cs)
+}
 
-    // Add addition gate
-    circuit.add_gate(circuit::Gate::Add(multiply_result_idx, total_credits_returned_idx, final_score_idx));
+/// Public commitment to a borrower's public key: Poseidon of its two 128-bit halves. The program
+/// recomputes it from the signer, so it binds a proof to the account it was made for.
+pub fn borrower_commitment(borrower: &Key) -> FieldElement {
+    poseidon::hash(&key_halves(borrower))
+}
 
-    // Set the output to the final score
-    circuit.set_output(score_value.clone());
+/// Commitment to the balances a borrower's score is computed from: Poseidon of the total credits
+/// returned, the credit token balance and the reputation. The program recomputes it from the
+/// borrower's token account and `OrganizationInfo`, so the prover cannot claim balances it does not
+/// have.
+pub fn record_commitment(total_credits_returned: u64, carbon_credits: u64, reputation: u64) -> FieldElement {
+    poseidon::hash(&[total_credits_returned, carbon_credits, reputation].map(FieldElement::from_u64))
+}
 
-    // Generate and verify the proof
-    let proof = circuit.generate_proof("eligibity_proof.bin");
-    let is_valid = circuit.verify_proof("eligibity_proof.bin");
-    println!("No-op Proof is valid: {}", is_valid);
+/// The public inputs of an eligibility proof as the 32-byte big-endian scalars the on-chain verifier
+/// takes.
+pub fn eligibility_public_inputs(
+    threshold: u64,
+    borrower: &Key,
+    total_credits_returned: u64,
+    carbon_credits: u64,
+    reputation: u64,
+) -> [[u8; 32]; ELIGIBILITY_PUBLIC_INPUTS] {
+    [
+        FieldElement::<Bn254Fr>::from_u64(threshold).to_bytes_be(),
+        borrower_commitment(borrower).to_bytes_be(),
+        record_commitment(total_credits_returned, carbon_credits, reputation).to_bytes_be(),
+    ]
+}
 
-    // Convert the result to an i32 for the return value
-    let eligibility_score = (score_value.get_value() % 1000000i32.to_bigint().unwrap())
-                            .to_string()
-                            .parse::<i32>()
-                            .unwrap_or(1);
+/// Deterministic development keys for the eligibility circuit, which the `carbon_credit` program
+/// accepts only when built with its `dev-eligibility-key` feature. The seed is public, so anyone can
+/// forge proofs under them; real keys come from a `setup::Ceremony`.
+#[cfg(feature = "dev-keys")]
+pub fn dev_eligibility_keys() -> Result<(ProvingKey, groth16::VerifyingKey)> {
+    use rand_chacha::ChaCha20Rng;
+    use rand_chacha::rand_core::SeedableRng;
 
-    // Read the proof file into a Vec<u8>
-    let proof_data = std::fs::read("noop_proof.bin").expect("Failed to read proof file");
+    let r1cs = eligibility_circuit(0, 0, 0, 0, &[0; 32])?.to_r1cs()?;
+    let mut rng = ChaCha20Rng::from_seed(ELIGIBILITY_DEVELOPMENT_SEED);
+    groth16::setup(&r1cs, &mut rng)
+}
 
-    // Return the eligibility score and the proof data
-    (if eligibility_score <= 0 { 1 } else { eligibility_score }, proof_data)
-}
\ No newline at end of file
+/// Prove off-chain that the borrower's on-chain record reaches `threshold`. Returns the proof in the
+/// encoding `create_lend_request` takes as instruction data; fails with `ZkError::Unsatisfied` if the
+/// score is too low.
+pub fn eligibility_proof(
+    pk: &ProvingKey,
+    borrower: &Key,
+    threshold: u64,
+    total_credits_returned: u64,
+    carbon_credits: u64,
+    reputation: u64,
+) -> Result<Vec<u8>> {
+    let r1cs = eligibility_circuit(total_credits_returned, carbon_credits, reputation, threshold, borrower)?.to_r1cs()?;
+    let witness = r1cs.generate_witness();
+    let proof = groth16::prove(pk, &r1cs, &witness, &mut OsRng)?;
+    Ok(groth16::proof_to_solana_bytes(&proof).to_vec())
+}
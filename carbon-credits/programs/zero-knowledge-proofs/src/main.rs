@@ -1,148 +1,332 @@
-mod circuit;
-mod r1cs;
-mod merkle;
-mod qap;
-mod field;
-mod proof;
-use zero_knowledge_proofs::eligibility_proof;
-use num_bigint::{ToBigInt};
-use circuit::Circuit;
-use crate::field::FieldElement;
-
-fn addition_proof() {
-    let mut circuit = Circuit::new();
-
-    let input1 = circuit.add_input(FieldElement::new(10.to_bigint().unwrap()));
-    let input2 = circuit.add_input(FieldElement::new(20.to_bigint().unwrap()));
-    let expected_sum = circuit.get_input(input1).expect("Invalid input index") +
-        circuit.get_input(input2).expect("Invalid input index");
-    let output_index = circuit.add_input(expected_sum.clone());
-    circuit.add_gate(circuit::Gate::Add(input1, input2, output_index));
-    circuit.set_output(expected_sum);
-    println!("Generating Addition Proof...");
-    circuit.generate_proof("addition_proof.bin");
-    let is_valid = circuit.verify_proof("addition_proof.bin");
-    println!("Addition Proof is valid: {}", is_valid);
-}
-fn merkle_tree_proof() {
-    let transactions = vec![
-        10.to_bigint().unwrap(),
-        20.to_bigint().unwrap(),
-        50.to_bigint().unwrap(),
-        80.to_bigint().unwrap(),
-    ];
-    let merkle_tree = merkle::MerkleTree::new(transactions.clone());
-    let leaf_index = 2;
-    let leaf_value = transactions[leaf_index].clone();
-    let merkle_path = merkle_tree.merkle_path(leaf_index);
-
-    let mut circuit = Circuit::new();
-
-    let leaf_index_var = circuit.add_input(FieldElement::new(leaf_value));
-    let mut current_hash_index = leaf_index_var;
-
-    for (sibling_hash, is_left) in merkle_path {
-        let sibling_index_var = circuit.add_input(FieldElement::new(sibling_hash.clone()));
-        let new_hash_value = if is_left {
-            merkle_tree.apply_hash(
-                circuit.get_input(sibling_index_var).expect("Invalid input index"),
-                circuit.get_input(current_hash_index).expect("Invalid input index"),
-            )
-        } else {
-            merkle_tree.apply_hash(
-                circuit.get_input(current_hash_index).expect("Invalid input index"),
-                circuit.get_input(sibling_index_var).expect("Invalid input index"),
-            )
-        };
-
-        let new_hash_index = circuit.add_input(new_hash_value.clone());
-        circuit.set_output(new_hash_value.clone());
-        circuit.add_gate(if is_left {
-            circuit::Gate::Add(sibling_index_var, current_hash_index, new_hash_index)
-        } else {
-            circuit::Gate::Add(current_hash_index, sibling_index_var, new_hash_index)
-        });
-
-        current_hash_index = new_hash_index;
-    }
-
-    circuit.set_output(FieldElement::new(merkle_tree.root.clone()));
-
-    println!("Expected Merkle root: {}", merkle_tree.root);
-    circuit.generate_proof("merkle_proof.bin");
-    let is_valid = circuit.verify_proof("merkle_proof.bin");
-    println!("Merkle Tree Proof is valid: {}", is_valid);
-}
-
-fn multiplication_proof() {
-    let mut circuit = Circuit::new();
-
-    let input1 = circuit.add_input(FieldElement::new(3.to_bigint().unwrap())); 
-    let input2 = circuit.add_input(FieldElement::new(4.to_bigint().unwrap())); 
-
-
-    let expected_product = circuit.get_input(input1).unwrap().get_value() * circuit.get_input(input2).unwrap().get_value();
-
-    let output_index = circuit.add_input(FieldElement::new(expected_product.clone())); // `output`
-    circuit.add_gate(circuit::Gate::Mul(input1, input2, output_index));
-    circuit.set_output(FieldElement::new(expected_product));
-
-    println!("Generating Multiplication Proof...");
-    circuit.generate_proof("multiplication_proof.bin");
-    let is_valid = circuit.verify_proof("multiplication_proof.bin");
-    println!("Multiplication Proof is valid: {}", is_valid);
-}
-// fn eligibility_proof(
-//     circuit: &mut Circuit,
-//     net_emissions: FieldElement,
-//     total_credits_returned: FieldElement,
-//     total_borrowed: FieldElement,
-//     debt: FieldElement,
-//     carbon_credits: FieldElement,
-//     reputation: FieldElement,
-//     org2_carbon_credits: FieldElement,
-//     org2_debt: FieldElement,
-// ) -> (i32, Vec<u8>) {
-//     let net_emissions_idx = circuit.add_input(net_emissions.clone());
-//     let total_credits_returned_idx = circuit.add_input(total_credits_returned.clone());
-//     let total_borrowed_idx = circuit.add_input(total_borrowed.clone());
-//     let debt_idx = circuit.add_input(debt.clone());
-//     let carbon_credits_idx = circuit.add_input(carbon_credits.clone());
-//     let reputation_idx = circuit.add_input(reputation.clone());
-//     let org2_carbon_credits_idx = circuit.add_input(org2_carbon_credits.clone());
-//     let org2_debt_idx = circuit.add_input(org2_debt.clone());
-//     let reputation_times_credits = circuit.get_input(reputation_idx).unwrap().get_value() * 
-//                                    circuit.get_input(carbon_credits_idx).unwrap().get_value();
-//     let multiply_result_idx = circuit.add_input(FieldElement::new(reputation_times_credits.clone()));
-//     circuit.add_gate(circuit::Gate::Mul(reputation_idx, carbon_credits_idx, multiply_result_idx));
-//     let score_value = circuit.get_input(multiply_result_idx).unwrap() + 
-//                       circuit.get_input(total_credits_returned_idx).unwrap();
-//     let final_score_idx = circuit.add_input(score_value.clone());
-//     circuit.add_gate(circuit::Gate::Add(multiply_result_idx, total_credits_returned_idx, final_score_idx));
-//     circuit.set_output(score_value.clone());
-//     let proof = circuit.generate_proof("noop_proof.bin");
-//     let is_valid = circuit.verify_proof("noop_proof.bin");
-//     println!("No-op Proof is valid: {}", is_valid);
-//     let eligibility_score = (score_value.get_value() % 1000000i32.to_bigint().unwrap())
-//                             .to_string()
-//                             .parse::<i32>()
-//                             .unwrap_or(1);
-//     let proof_data = std::fs::read("noop_proof.bin").expect("Failed to read proof file");
-//     (if eligibility_score <= 0 { 1 } else { eligibility_score }, proof_data)
-// }
-
-fn main() {
-    let (score, proof) = eligibility_proof(
-        100,  // net_emissions
-        50,   // total_credits_returned
-        30,   // total_borrowed
-        20,   // debt
-        40,   // carbon_credits
-        90,   // reputation
-        60,   // org2_carbon_credits
-        10,   // org2_debt
-    );
-
-    println!("Eligibility Score: {}", score);
-    println!("Proof Data: {:?}", proof);
-}
\ No newline at end of file
+//! `zkp`: set up, prove and verify the eligibility circuit, or any circom circuit, from the command
+//! line. Exit code 0 means success (and a valid proof for `verify`), 1 an invalid proof and 2 a
+//! usage or I/O error, so scripts and CI can branch on it.
+
+use std::collections::HashMap;
+use std::process::ExitCode;
+use num_bigint::BigInt;
+use rand::rngs::OsRng;
+use serde::Deserialize;
+use zero_knowledge_proofs::error::ZkError;
+use zero_knowledge_proofs::field::{Bn254Fr, FieldElement};
+use zero_knowledge_proofs::format::{self, Header};
+use zero_knowledge_proofs::groth16::{self, Groth16Proof, ProvingKey, VerifyingKey};
+use zero_knowledge_proofs::proof::Proof;
+use zero_knowledge_proofs::r1cs::R1CS;
+use zero_knowledge_proofs::sparse_merkle::Key;
+use zero_knowledge_proofs::{eligibility_circuit, iden3, setup};
+
+const USAGE: &str = "\
+usage: zkp <command> [options]
+
+commands:
+  setup            [--r1cs circuit.r1cs] [--dev] [--pk FILE] [--vk FILE] [--out-r1cs FILE]
+                   Single-party Groth16 keys for the eligibility circuit, or for a circom .r1cs;
+                   production keys come from a multi-party setup::Ceremony. --dev uses the public
+                   development keys (needs the dev-keys feature), which anyone can forge proofs for.
+  prove            --inputs inputs.json | --r1cs circuit.r1cs --wtns witness.wtns
+                   [--pk FILE] [--proof FILE] [--public FILE]
+  verify           --vk vk.bin --proof proof.bin --public public.json
+  inspect          FILE
+  export-verifier  [--vk FILE] [--name CONST] [--out FILE]
+
+Files default to generated-proofs/{proving_key,verifying_key,r1cs_file,proof}.bin and
+generated-proofs/public.json. inputs.json holds the eligibility inputs: borrower (hex public key),
+threshold, total_credits_returned, carbon_credits and reputation.
+
+exit status: 0 on success, 1 if verify rejects the proof, 2 on any other error.";
+
+const DEFAULT_PROVING_KEY: &str = "generated-proofs/proving_key.bin";
+const DEFAULT_VERIFYING_KEY: &str = "generated-proofs/verifying_key.bin";
+const DEFAULT_R1CS: &str = "generated-proofs/r1cs_file.bin";
+const DEFAULT_PROOF: &str = "generated-proofs/proof.bin";
+const DEFAULT_PUBLIC: &str = "generated-proofs/public.json";
+
+/// Why a command failed; each maps to an exit code.
+enum Failure {
+    Usage(String),
+    Error(ZkError),
+    InvalidProof,
+}
+
+impl From<ZkError> for Failure {
+    fn from(error: ZkError) -> Self {
+        Failure::Error(error)
+    }
+}
+
+impl From<std::io::Error> for Failure {
+    fn from(error: std::io::Error) -> Self {
+        Failure::Error(ZkError::Io(error))
+    }
+}
+
+/// Inputs of the eligibility circuit, as read from `--inputs`.
+#[derive(Deserialize)]
+struct EligibilityInputs {
+    borrower: String,
+    threshold: u64,
+    total_credits_returned: u64,
+    carbon_credits: u64,
+    reputation: u64,
+}
+
+/// `--name value` options, `--name` switches and at most one positional argument.
+struct Args {
+    options: HashMap<String, String>,
+    flags: Vec<String>,
+    positional: Option<String>,
+}
+
+impl Args {
+    fn parse(args: &[String], options: &[&str], flags: &[&str]) -> Result<Self, Failure> {
+        let mut parsed = Args { options: HashMap::new(), flags: Vec::new(), positional: None };
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            match arg.strip_prefix("--") {
+                Some(name) if options.contains(&name) => {
+                    let value = args.next().ok_or_else(|| Failure::Usage(format!("--{name} needs a value")))?;
+                    parsed.options.insert(name.to_string(), value.clone());
+                },
+                Some(name) if flags.contains(&name) => parsed.flags.push(name.to_string()),
+                Some(name) => return Err(Failure::Usage(format!("unknown option --{name}"))),
+                None if parsed.positional.is_none() => parsed.positional = Some(arg.clone()),
+                None => return Err(Failure::Usage(format!("unexpected argument {arg}"))),
+            }
+        }
+        Ok(parsed)
+    }
+
+    fn get(&self, name: &str) -> Option<&str> {
+        self.options.get(name).map(String::as_str)
+    }
+
+    fn get_or<'a>(&'a self, name: &str, default: &'a str) -> &'a str {
+        self.get(name).unwrap_or(default)
+    }
+
+    fn require(&self, name: &str) -> Result<&str, Failure> {
+        self.get(name).ok_or_else(|| Failure::Usage(format!("--{name} is required")))
+    }
+
+    fn flag(&self, name: &str) -> bool {
+        self.flags.iter().any(|flag| flag == name)
+    }
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let Some((command, rest)) = args.split_first() else {
+        eprintln!("{USAGE}");
+        return ExitCode::from(2);
+    };
+
+    let result = match command.as_str() {
+        "setup" => Args::parse(rest, &["r1cs", "pk", "vk", "out-r1cs"], &["dev"]).and_then(|args| run_setup(&args)),
+        "prove" => Args::parse(rest, &["inputs", "r1cs", "wtns", "pk", "proof", "public"], &[]).and_then(|args| run_prove(&args)),
+        "verify" => Args::parse(rest, &["vk", "proof", "public"], &[]).and_then(|args| run_verify(&args)),
+        "inspect" => Args::parse(rest, &[], &[]).and_then(|args| run_inspect(&args)),
+        "export-verifier" => Args::parse(rest, &["vk", "name", "out"], &[]).and_then(|args| run_export(&args)),
+        "help" | "--help" | "-h" => {
+            println!("{USAGE}");
+            Ok(())
+        },
+        other => Err(Failure::Usage(format!("unknown command {other}"))),
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(Failure::InvalidProof) => {
+            eprintln!("proof is invalid");
+            ExitCode::from(1)
+        },
+        Err(Failure::Usage(message)) => {
+            eprintln!("error: {message}\n\n{USAGE}");
+            ExitCode::from(2)
+        },
+        Err(Failure::Error(error)) => {
+            eprintln!("error: {error}");
+            ExitCode::from(2)
+        },
+    }
+}
+
+fn run_setup(args: &Args) -> Result<(), Failure> {
+    let r1cs = match args.get("r1cs") {
+        Some(path) => iden3::read_r1cs::<Bn254Fr>(path)?.1,
+        None => eligibility_circuit(0, 0, 0, 0, &[0; 32])?.to_r1cs()?,
+    };
+    let (pk, vk) = match (args.flag("dev"), args.get("r1cs")) {
+        (true, None) => dev_eligibility_keys()?,
+        (true, Some(_)) => return Err(Failure::Usage("--dev only applies to the eligibility circuit".into())),
+        (false, _) => groth16::setup(&r1cs, &mut OsRng)?,
+    };
+
+    let (pk_path, vk_path) = (args.get_or("pk", DEFAULT_PROVING_KEY), args.get_or("vk", DEFAULT_VERIFYING_KEY));
+    let r1cs_path = args.get_or("out-r1cs", DEFAULT_R1CS);
+    for path in [pk_path, vk_path, r1cs_path] {
+        create_parent(path)?;
+    }
+    setup::save_proving_key(&pk, pk_path)?;
+    setup::save_verifying_key(&vk, vk_path)?;
+    r1cs.save_to_binary(r1cs_path)?;
+    println!("{} constraints, {} public inputs", r1cs.constraints.len(), r1cs.num_public());
+    println!("wrote {pk_path}, {vk_path} and {r1cs_path}");
+    Ok(())
+}
+
+#[cfg(feature = "dev-keys")]
+fn dev_eligibility_keys() -> Result<(ProvingKey, VerifyingKey), Failure> {
+    Ok(zero_knowledge_proofs::dev_eligibility_keys()?)
+}
+
+#[cfg(not(feature = "dev-keys"))]
+fn dev_eligibility_keys() -> Result<(ProvingKey, VerifyingKey), Failure> {
+    Err(Failure::Usage("--dev needs zkp built with the dev-keys feature".into()))
+}
+
+fn run_prove(args: &Args) -> Result<(), Failure> {
+    let r1cs: R1CS = match (args.get("inputs"), args.get("r1cs"), args.get("wtns")) {
+        (Some(path), None, None) => {
+            let inputs: EligibilityInputs = serde_json::from_str(&std::fs::read_to_string(path)?)
+                .map_err(|error| Failure::Usage(format!("{path}: {error}")))?;
+            let borrower = parse_key(&inputs.borrower)?;
+            eligibility_circuit(
+                inputs.total_credits_returned,
+                inputs.carbon_credits,
+                inputs.reputation,
+                inputs.threshold,
+                &borrower,
+            )?
+            .to_r1cs()?
+        },
+        (None, Some(r1cs), Some(wtns)) => iden3::read_circuit(r1cs, wtns)?,
+        _ => return Err(Failure::Usage("prove takes either --inputs or both --r1cs and --wtns".into())),
+    };
+
+    let pk = setup::load_proving_key(args.get_or("pk", DEFAULT_PROVING_KEY))?;
+    let witness = r1cs.generate_witness();
+    let proof = r1cs.generate_proof(&pk, &witness, &mut OsRng)?;
+
+    let (proof_path, public_path) = (args.get_or("proof", DEFAULT_PROOF), args.get_or("public", DEFAULT_PUBLIC));
+    create_parent(proof_path)?;
+    create_parent(public_path)?;
+    proof.save_to_binary(proof_path)?;
+    let public: Vec<String> = proof.public_inputs.iter().map(BigInt::to_string).collect();
+    let public = serde_json::to_string_pretty(&public).map_err(|error| ZkError::Serialization(error.to_string()))?;
+    std::fs::write(public_path, public + "\n")?;
+    println!("wrote {proof_path} and {public_path}");
+    Ok(())
+}
+
+fn run_verify(args: &Args) -> Result<(), Failure> {
+    let vk = setup::load_verifying_key(args.require("vk")?)?;
+    let proof = Proof::load_from_binary(args.require("proof")?)?;
+    let public_path = args.require("public")?;
+    let public: Vec<String> = serde_json::from_str(&std::fs::read_to_string(public_path)?)
+        .map_err(|error| Failure::Usage(format!("{public_path}: {error}")))?;
+    let public = public.iter().map(|value| parse_field_element(value)).collect::<Result<Vec<_>, _>>()?;
+
+    let Ok(groth16_proof) = groth16::from_bytes::<Groth16Proof>(&proof.proof) else {
+        return Err(Failure::InvalidProof);
+    };
+    if public.len() + 1 != vk.gamma_abc_g1.len() || !groth16::verify(&vk, &public, &groth16_proof) {
+        return Err(Failure::InvalidProof);
+    }
+    println!("proof is valid");
+    Ok(())
+}
+
+fn run_inspect(args: &Args) -> Result<(), Failure> {
+    let path = args.positional.as_deref().ok_or_else(|| Failure::Usage("inspect needs a file".into()))?;
+    let magic = std::fs::read(path)?.get(..4).map(<[u8]>::to_vec).unwrap_or_default();
+
+    match magic.as_slice() {
+        b"r1cs" => {
+            let (header, r1cs) = iden3::read_r1cs::<Bn254Fr>(path)?;
+            println!("kind:        iden3 constraint system");
+            println!("wires:       {}", header.num_wires);
+            println!("public:      {} outputs, {} inputs", header.num_public_outputs, header.num_public_inputs);
+            println!("private:     {} inputs", header.num_private_inputs);
+            println!("constraints: {}", r1cs.constraints.len());
+        },
+        b"wtns" => {
+            let witness = iden3::read_wtns::<Bn254Fr>(path)?;
+            println!("kind:        iden3 witness");
+            println!("values:      {}", witness.len());
+        },
+        _ => {
+            let header = format::read_header(path)?;
+            let (_, body) = format::read_file(path, header.magic)?;
+            print_header(&header, body.len());
+            if header.magic == format::R1CS_MAGIC {
+                let r1cs = R1CS::<Bn254Fr>::load_from_binary(path)?;
+                println!("public:      {}", r1cs.num_public());
+            }
+        },
+    }
+    Ok(())
+}
+
+fn print_header(header: &Header, body_len: usize) {
+    let kind = match header.magic {
+        format::R1CS_MAGIC => "constraint system",
+        format::PROOF_MAGIC => "proof",
+        setup::PROVING_KEY_MAGIC => "proving key",
+        setup::VERIFYING_KEY_MAGIC => "verifying key",
+        setup::CEREMONY_MAGIC => "setup ceremony",
+        setup::POWERS_OF_TAU_MAGIC => "powers of tau",
+        _ => "unknown",
+    };
+    println!("kind:        {kind} ({})", String::from_utf8_lossy(&header.magic));
+    println!("version:     {}", header.version);
+    println!("field:       {}", format::field_name(&header.field));
+    println!("constraints: {}", header.num_constraints);
+    println!("variables:   {}", header.num_variables);
+    println!("body:        {body_len} bytes, checksum ok");
+}
+
+fn run_export(args: &Args) -> Result<(), Failure> {
+    let vk = setup::load_verifying_key(args.get_or("vk", DEFAULT_VERIFYING_KEY))?;
+    let source = setup::export_verifying_key(&vk, args.get_or("name", "ELIGIBILITY_VERIFYING_KEY"));
+    match args.get("out") {
+        Some(path) => {
+            create_parent(path)?;
+            std::fs::write(path, source)?;
+            println!("wrote {path}");
+        },
+        None => print!("{source}"),
+    }
+    Ok(())
+}
+
+fn create_parent(path: &str) -> Result<(), Failure> {
+    if let Some(parent) = std::path::Path::new(path).parent().filter(|parent| !parent.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent)?;
+    }
+    Ok(())
+}
+
+/// A 32-byte public key in hex, with or without a `0x` prefix.
+fn parse_key(hex: &str) -> Result<Key, Failure> {
+    let hex = hex.strip_prefix("0x").unwrap_or(hex);
+    let invalid = || Failure::Usage(format!("borrower must be 64 hex digits, got {hex:?}"));
+    if hex.len() != 64 || !hex.is_ascii() {
+        return Err(invalid());
+    }
+    let mut key = [0u8; 32];
+    for (byte, digits) in key.iter_mut().zip(hex.as_bytes().chunks(2)) {
+        *byte = u8::from_str_radix(std::str::from_utf8(digits).map_err(|_| invalid())?, 16).map_err(|_| invalid())?;
+    }
+    Ok(key)
+}
+
+/// A decimal public input, which must already be reduced.
+fn parse_field_element(value: &str) -> Result<FieldElement, Failure> {
+    let parsed: BigInt = value.parse().map_err(|_| Failure::Usage(format!("{value:?} is not a decimal number")))?;
+    let element = FieldElement::new(parsed.clone());
+    if element.get_value() != parsed {
+        return Err(Failure::Usage(format!("{value} is not a canonical field element")));
+    }
+    Ok(element)
+}
@@ -1,7 +1,13 @@
 use num_bigint::BigInt;
-use crate::field::FieldElement;
+use crate::circuit::{Circuit, Wire};
+use crate::field::{Bn254Fr, FieldElement};
+use crate::gadgets;
+use crate::poseidon;
 
 
+/// Binary Merkle tree over BN254 elements, with two-to-one Poseidon as the node hash. The leaves are
+/// padded with zeros to a power of two, so every path has the same length and does not give away
+/// where its leaf sits.
 pub struct MerkleTree {
     pub root: BigInt,
     pub leaves: Vec<BigInt>,
@@ -16,63 +22,112 @@ impl MerkleTree {
         }
     }
 
+    /// Number of levels above the leaves, and the length of every path.
+    pub fn depth(&self) -> usize {
+        self.leaves.len().next_power_of_two().trailing_zeros() as usize
+    }
+
+    /// Siblings from the leaf up, each with whether the current node is the left child. Always
+    /// `depth()` long.
     pub fn merkle_path(&self, index: usize) -> Vec<(BigInt, bool)> {
-        let mut path = Vec::new();
+        let mut path = Vec::with_capacity(self.depth());
         let mut current_index = index;
-        let mut nodes = self.leaves.clone();
+        let mut nodes = MerkleTree::padded(&self.leaves);
 
         while nodes.len() > 1 {
-            let next_level: Vec<BigInt> = nodes
-                .chunks(2)
-                .map(|chunk| {
-                    if chunk.len() == 2 {
-                        MerkleTree::hash(&chunk[0], &chunk[1])
-                    } else {
-                        chunk[0].clone() // Handle last single node in an odd-numbered level
-                    }
-                })
-                .collect();
-
-            let sibling_index = if current_index % 2 == 0 {
-                current_index + 1
-            } else {
-                current_index - 1
-            };
-
-            if sibling_index < nodes.len() {
-                path.push((nodes[sibling_index].clone(), current_index % 2 == 0));
-            }
+            let is_left = current_index.is_multiple_of(2);
+            let sibling_index = if is_left { current_index + 1 } else { current_index - 1 };
+            path.push((nodes[sibling_index].clone(), is_left));
 
             current_index /= 2;
-            nodes = next_level;
+            nodes = MerkleTree::next_level(&nodes);
         }
 
         path
     }
 
-    fn compute_root(leaves: &Vec<BigInt>) -> BigInt {
-        let mut nodes = leaves.clone();
+    fn compute_root(leaves: &[BigInt]) -> BigInt {
+        let mut nodes = MerkleTree::padded(leaves);
         while nodes.len() > 1 {
-            nodes = nodes.chunks(2).map(|chunk| {
-                if chunk.len() == 2 {
-                    MerkleTree::hash(&chunk[0], &chunk[1])
-                } else {
-                    chunk[0].clone()
-                }
-            }).collect();
+            nodes = MerkleTree::next_level(&nodes);
         }
         nodes[0].clone()
     }
+
+    /// `leaves` followed by zero leaves up to the next power of two.
+    fn padded(leaves: &[BigInt]) -> Vec<BigInt> {
+        let mut nodes = leaves.to_vec();
+        nodes.resize(leaves.len().next_power_of_two(), BigInt::from(0));
+        nodes
+    }
+
+    fn next_level(nodes: &[BigInt]) -> Vec<BigInt> {
+        nodes.chunks(2).map(|pair| MerkleTree::hash(&pair[0], &pair[1])).collect()
+    }
+
+    /// Recompute the root from `leaf` and a path as returned by `merkle_path`.
+    pub fn root_from_path(leaf: &BigInt, path: &[(BigInt, bool)]) -> BigInt {
+        path.iter().fold(leaf.clone(), |current, (sibling, is_left)| {
+            if *is_left {
+                MerkleTree::hash(&current, sibling)
+            } else {
+                MerkleTree::hash(sibling, &current)
+            }
+        })
+    }
+
+    /// Check that `leaf` sits under `root` along `path`.
+    pub fn verify_path(root: &BigInt, leaf: &BigInt, path: &[(BigInt, bool)]) -> bool {
+        FieldElement::<Bn254Fr>::new(MerkleTree::root_from_path(leaf, path)) == FieldElement::new(root.clone())
+    }
+
+    /// Poseidon of the two children, reduced into the field first.
     pub fn hash(left: &BigInt, right: &BigInt) -> BigInt {
-        let combined = left + right;
-        combined % BigInt::from(1_000_000_007) 
+        let combined = poseidon::hash(&[FieldElement::new(left.clone()), FieldElement::new(right.clone())]);
+        combined.get_value()
     }
 
     /// Hash two FieldElements to create a new FieldElement
     pub fn apply_hash(&self, a: &FieldElement, b: &FieldElement) -> FieldElement {
-        // Example hash function: (a + b) % modulus
-        assert_eq!(a.get_modulus(), b.get_modulus(), "Moduli must match for hashing");
-        let new_value = (a.get_value() + b.get_value()) % a.get_modulus(); 
-        FieldElement::new(new_value)
+        poseidon::hash(&[*a, *b])
     }
 }
+
+/// A Merkle path as circuit wires: the sibling at each level and a boolean wire that is 1 when the
+/// current node is the left child.
+pub struct PathWires {
+    pub siblings: Vec<Wire>,
+    pub is_left: Vec<Wire>,
+}
+
+impl PathWires {
+    /// Allocate `path` (as returned by `MerkleTree::merkle_path`) as private wires. The direction bits
+    /// are constrained to be boolean.
+    pub fn private(cs: &mut Circuit, path: &[(BigInt, bool)]) -> Self {
+        let mut wires = PathWires { siblings: Vec::with_capacity(path.len()), is_left: Vec::with_capacity(path.len()) };
+        for (sibling, is_left) in path {
+            wires.siblings.push(cs.private_input(FieldElement::new(sibling.clone())));
+            wires.is_left.push(gadgets::boolean_input(cs, *is_left));
+        }
+        wires
+    }
+}
+
+/// In-circuit counterpart of `MerkleTree::root_from_path`. Each level orders the pair with one
+/// selection, so the direction bits stay as hidden as the siblings; since every path of a tree is
+/// `depth()` long, neither does the shape of the circuit depend on the leaf.
+pub fn root_gadget(cs: &mut Circuit, leaf: Wire, path: &PathWires) -> Wire {
+    let one = FieldElement::one();
+    path.siblings.iter().zip(&path.is_left).fold(leaf, |current, (sibling, is_left)| {
+        let left = gadgets::select(cs, *is_left, current, *sibling);
+        let right = cs.linear_combination(&[(one, current), (one, *sibling), (-one, left)]);
+        poseidon::hash_gadget(cs, &[left, right])
+    })
+}
+
+/// Constrain `leaf` to sit under `root` along `path`. With a private leaf and path and a public root,
+/// a proof shows membership in the tree without revealing which leaf it is.
+pub fn membership_gadget(cs: &mut Circuit, leaf: Wire, path: &PathWires, root: Wire) {
+    let computed = root_gadget(cs, leaf, path);
+    cs.assert_equal(computed, root);
+}
@@ -0,0 +1,354 @@
+//! Dense univariate polynomials over a prime field, and radix-2 NTTs over its power-of-two
+//! multiplicative subgroups and their cosets.
+
+use serde::{Serialize, Deserialize};
+use std::ops::{Add, Mul, Neg, Sub};
+use crate::error::{Result, ZkError};
+use crate::field::{Bn254Fr, FieldElement, FieldParams};
+
+/// Below this many coefficients per factor, schoolbook multiplication beats going through NTTs.
+const NTT_MUL_THRESHOLD: usize = 64;
+
+/// Dense univariate polynomial, coefficients from the constant term up. Trailing zero coefficients
+/// are trimmed, so the zero polynomial has none.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct Polynomial<F: FieldParams = Bn254Fr> {
+    coefficients: Vec<FieldElement<F>>,
+}
+
+/// The subgroup of `size` (a power of two) roots of unity, over which NTTs evaluate and interpolate
+/// polynomials of degree below `size`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct EvaluationDomain<F: FieldParams = Bn254Fr> {
+    size: usize,
+    generator: FieldElement<F>,
+    generator_inv: FieldElement<F>,
+    size_inv: FieldElement<F>,
+    /// Shift of the coset used by `coset_fft`. The multiplicative generator lies outside every
+    /// power-of-two subgroup, so the coset never meets the domain itself.
+    coset_offset: FieldElement<F>,
+    coset_offset_inv: FieldElement<F>,
+}
+
+impl<F: FieldParams> Default for Polynomial<F> {
+    fn default() -> Self {
+        Self::zero()
+    }
+}
+
+impl<F: FieldParams> Polynomial<F> {
+    pub fn new(coefficients: Vec<FieldElement<F>>) -> Self {
+        let mut polynomial = Polynomial { coefficients };
+        polynomial.trim();
+        polynomial
+    }
+
+    pub fn zero() -> Self {
+        Polynomial { coefficients: Vec::new() }
+    }
+
+    pub fn constant(value: FieldElement<F>) -> Self {
+        Self::new(vec![value])
+    }
+
+    pub fn coefficients(&self) -> &[FieldElement<F>] {
+        &self.coefficients
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.coefficients.is_empty()
+    }
+
+    /// `None` for the zero polynomial.
+    pub fn degree(&self) -> Option<usize> {
+        self.coefficients.len().checked_sub(1)
+    }
+
+    /// Horner evaluation at `x`.
+    pub fn evaluate(&self, x: &FieldElement<F>) -> FieldElement<F> {
+        self.coefficients.iter().rev().fold(FieldElement::zero(), |acc, coeff| acc * x + coeff)
+    }
+
+    pub fn add(&self, other: &Polynomial<F>) -> Polynomial<F> {
+        let (longer, shorter) = if self.coefficients.len() >= other.coefficients.len() {
+            (self, other)
+        } else {
+            (other, self)
+        };
+        let mut coefficients = longer.coefficients.clone();
+        for (coeff, term) in coefficients.iter_mut().zip(&shorter.coefficients) {
+            *coeff += term;
+        }
+        Self::new(coefficients)
+    }
+
+    pub fn sub(&self, other: &Polynomial<F>) -> Polynomial<F> {
+        self.add(&other.negate())
+    }
+
+    pub fn negate(&self) -> Polynomial<F> {
+        Polynomial { coefficients: self.coefficients.iter().map(|coeff| -coeff).collect() }
+    }
+
+    pub fn scale(&self, factor: &FieldElement<F>) -> Polynomial<F> {
+        Self::new(self.coefficients.iter().map(|coeff| coeff * factor).collect())
+    }
+
+    /// Product of two polynomials. Large ones are multiplied pointwise over an NTT domain, in
+    /// `O(n log n)`; small ones, or ones too large for the field's two-adicity, the schoolbook way.
+    pub fn mul(&self, other: &Polynomial<F>) -> Polynomial<F> {
+        if self.is_zero() || other.is_zero() {
+            return Self::zero();
+        }
+        let product_len = self.coefficients.len() + other.coefficients.len() - 1;
+        if self.coefficients.len().min(other.coefficients.len()) >= NTT_MUL_THRESHOLD
+            && let Ok(domain) = EvaluationDomain::new(product_len)
+        {
+            let transform = |coefficients: &[FieldElement<F>]| {
+                let mut values = coefficients.to_vec();
+                values.resize(domain.size, FieldElement::zero());
+                domain.forward(values)
+            };
+            let (left, right) = (transform(&self.coefficients), transform(&other.coefficients));
+            let product: Vec<FieldElement<F>> = left.iter().zip(&right).map(|(a, b)| a * b).collect();
+            return Self::new(domain.inverse(product));
+        }
+
+        let mut coefficients = vec![FieldElement::zero(); product_len];
+        for (i, a) in self.coefficients.iter().enumerate() {
+            for (j, b) in other.coefficients.iter().enumerate() {
+                coefficients[i + j] += a * b;
+            }
+        }
+        Self::new(coefficients)
+    }
+
+    /// Long division: `self = quotient * divisor + remainder` with `deg(remainder) < deg(divisor)`.
+    /// Fails with `ZkError::NoInverse` on a zero divisor.
+    pub fn div_rem(&self, divisor: &Polynomial<F>) -> Result<(Polynomial<F>, Polynomial<F>)> {
+        let divisor_degree = divisor.degree().ok_or(ZkError::NoInverse)?;
+        let Some(degree) = self.degree().filter(|degree| *degree >= divisor_degree) else {
+            return Ok((Self::zero(), self.clone()));
+        };
+
+        let leading_inverse = divisor.coefficients[divisor_degree].inv()?;
+        let mut remainder = self.coefficients.clone();
+        let mut quotient = vec![FieldElement::zero(); degree - divisor_degree + 1];
+        for shift in (0..quotient.len()).rev() {
+            let factor = remainder[shift + divisor_degree] * leading_inverse;
+            quotient[shift] = factor;
+            for (i, coeff) in divisor.coefficients.iter().enumerate() {
+                remainder[shift + i] -= factor * coeff;
+            }
+        }
+        remainder.truncate(divisor_degree);
+        Ok((Self::new(quotient), Self::new(remainder)))
+    }
+
+    /// `prod (x - point)` over `points`.
+    pub fn vanishing(points: &[FieldElement<F>]) -> Polynomial<F> {
+        points.iter().fold(Self::constant(FieldElement::one()), |product, point| {
+            product.mul(&Self::new(vec![-*point, FieldElement::one()]))
+        })
+    }
+
+    /// Lagrange interpolation: the unique polynomial of degree below `points.len()` through `points`.
+    /// Repeated x coordinates fail with `ZkError::NoInverse`. Takes `O(n^2)`; for values over an
+    /// `EvaluationDomain` use its `interpolate` instead.
+    pub fn interpolate(points: &[(FieldElement<F>, FieldElement<F>)]) -> Result<Polynomial<F>> {
+        let xs: Vec<FieldElement<F>> = points.iter().map(|(x, _)| *x).collect();
+        let vanishing = Self::vanishing(&xs);
+        points.iter().try_fold(Self::zero(), |sum, (x, y)| {
+            // L(x) = Z(x) / ((x - x_i) * Z'(x_i)), which is 1 at x_i and 0 at the other points.
+            let (numerator, _) = vanishing.div_rem(&Self::new(vec![-*x, FieldElement::one()]))?;
+            let denominator = numerator.evaluate(x);
+            Ok(sum.add(&numerator.scale(&(*y * denominator.inv()?))))
+        })
+    }
+
+    fn trim(&mut self) {
+        while self.coefficients.last().is_some_and(|coeff| coeff.is_zero()) {
+            self.coefficients.pop();
+        }
+    }
+}
+
+impl<F: FieldParams> Add for &Polynomial<F> {
+    type Output = Polynomial<F>;
+
+    fn add(self, other: &Polynomial<F>) -> Polynomial<F> {
+        Polynomial::add(self, other)
+    }
+}
+
+impl<F: FieldParams> Sub for &Polynomial<F> {
+    type Output = Polynomial<F>;
+
+    fn sub(self, other: &Polynomial<F>) -> Polynomial<F> {
+        Polynomial::sub(self, other)
+    }
+}
+
+impl<F: FieldParams> Mul for &Polynomial<F> {
+    type Output = Polynomial<F>;
+
+    fn mul(self, other: &Polynomial<F>) -> Polynomial<F> {
+        Polynomial::mul(self, other)
+    }
+}
+
+impl<F: FieldParams> Neg for &Polynomial<F> {
+    type Output = Polynomial<F>;
+
+    fn neg(self) -> Polynomial<F> {
+        self.negate()
+    }
+}
+
+impl<F: FieldParams> EvaluationDomain<F> {
+    /// The smallest domain with at least `min_size` points. Fails with `ZkError::DomainSize` if the
+    /// field has no subgroup that large.
+    pub fn new(min_size: usize) -> Result<Self> {
+        let too_large = || ZkError::DomainSize {
+            required: min_size,
+            available: 1usize.checked_shl(FieldElement::<F>::two_adicity()).unwrap_or(usize::MAX),
+        };
+        let size = min_size.max(1).checked_next_power_of_two().ok_or_else(too_large)?;
+        let generator = FieldElement::root_of_unity(size.trailing_zeros()).ok_or_else(too_large)?;
+        let coset_offset = FieldElement::multiplicative_generator();
+        Ok(EvaluationDomain {
+            size,
+            generator,
+            generator_inv: generator.inv()?,
+            size_inv: FieldElement::from_u64(size as u64).inv()?,
+            coset_offset,
+            coset_offset_inv: coset_offset.inv()?,
+        })
+    }
+
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// The primitive `size`-th root of unity `w`; point `i` of the domain is `w^i`.
+    pub fn generator(&self) -> FieldElement<F> {
+        self.generator
+    }
+
+    pub fn coset_offset(&self) -> FieldElement<F> {
+        self.coset_offset
+    }
+
+    pub fn elements(&self) -> Vec<FieldElement<F>> {
+        powers(FieldElement::one(), self.generator, self.size)
+    }
+
+    /// Evaluations at every domain point, in order, of the polynomial with `coefficients`. Fails with
+    /// `ZkError::DomainSize` if there are more coefficients than points; the same goes for the other
+    /// transforms.
+    pub fn fft(&self, coefficients: &[FieldElement<F>]) -> Result<Vec<FieldElement<F>>> {
+        Ok(self.forward(self.padded(coefficients)?))
+    }
+
+    /// Coefficients of the polynomial taking `evaluations` at the domain points.
+    pub fn ifft(&self, evaluations: &[FieldElement<F>]) -> Result<Vec<FieldElement<F>>> {
+        Ok(self.inverse(self.padded(evaluations)?))
+    }
+
+    /// Like `fft`, but at the points `offset * w^i` of the coset.
+    pub fn coset_fft(&self, coefficients: &[FieldElement<F>]) -> Result<Vec<FieldElement<F>>> {
+        let shifted: Vec<FieldElement<F>> = coefficients
+            .iter()
+            .zip(powers(FieldElement::one(), self.coset_offset, coefficients.len()))
+            .map(|(coeff, power)| coeff * power)
+            .collect();
+        self.fft(&shifted)
+    }
+
+    /// Inverse of `coset_fft`.
+    pub fn coset_ifft(&self, evaluations: &[FieldElement<F>]) -> Result<Vec<FieldElement<F>>> {
+        let mut coefficients = self.ifft(evaluations)?;
+        for (coeff, power) in coefficients.iter_mut().zip(powers(FieldElement::one(), self.coset_offset_inv, self.size)) {
+            *coeff *= power;
+        }
+        Ok(coefficients)
+    }
+
+    pub fn interpolate(&self, evaluations: &[FieldElement<F>]) -> Result<Polynomial<F>> {
+        Ok(Polynomial::new(self.ifft(evaluations)?))
+    }
+
+    /// `Z(x) = x^size - 1`, which vanishes exactly on the domain.
+    pub fn vanishing_polynomial(&self) -> Polynomial<F> {
+        let mut coefficients = vec![FieldElement::zero(); self.size + 1];
+        coefficients[0] = -FieldElement::one();
+        coefficients[self.size] = FieldElement::one();
+        Polynomial::new(coefficients)
+    }
+
+    pub fn evaluate_vanishing(&self, x: &FieldElement<F>) -> FieldElement<F> {
+        x.pow(self.size as u64) - FieldElement::one()
+    }
+
+    fn padded(&self, values: &[FieldElement<F>]) -> Result<Vec<FieldElement<F>>> {
+        if values.len() > self.size {
+            return Err(ZkError::DomainSize { required: values.len(), available: self.size });
+        }
+        let mut padded = values.to_vec();
+        padded.resize(self.size, FieldElement::zero());
+        Ok(padded)
+    }
+
+    /// NTT of exactly `size` values.
+    fn forward(&self, mut values: Vec<FieldElement<F>>) -> Vec<FieldElement<F>> {
+        ntt(&mut values, self.generator);
+        values
+    }
+
+    /// Inverse NTT of exactly `size` values.
+    fn inverse(&self, mut values: Vec<FieldElement<F>>) -> Vec<FieldElement<F>> {
+        ntt(&mut values, self.generator_inv);
+        for value in &mut values {
+            *value *= self.size_inv;
+        }
+        values
+    }
+}
+
+fn powers<F: FieldParams>(start: FieldElement<F>, base: FieldElement<F>, count: usize) -> Vec<FieldElement<F>> {
+    std::iter::successors(Some(start), |power| Some(power * base)).take(count).collect()
+}
+
+/// In-place iterative Cooley–Tukey NTT: `values[i]` becomes `sum_j values[j] * root^(i * j)`.
+/// `values.len()` must be a power of two and `root` a primitive root of unity of that order.
+fn ntt<F: FieldParams>(values: &mut [FieldElement<F>], root: FieldElement<F>) {
+    let n = values.len();
+    let log_n = n.trailing_zeros();
+    if n <= 1 {
+        return;
+    }
+    for i in 0..n {
+        let j = i.reverse_bits() >> (usize::BITS - log_n);
+        if i < j {
+            values.swap(i, j);
+        }
+    }
+
+    let mut half = 1;
+    while half < n {
+        // A primitive (2 * half)-th root of unity.
+        let step = root.pow((n / (2 * half)) as u64);
+        let twiddles = powers(FieldElement::one(), step, half);
+        for chunk in values.chunks_mut(2 * half) {
+            let (low, high) = chunk.split_at_mut(half);
+            for ((a, b), twiddle) in low.iter_mut().zip(high.iter_mut()).zip(&twiddles) {
+                let t = *b * twiddle;
+                *b = *a - t;
+                *a += t;
+            }
+        }
+        half *= 2;
+    }
+}
@@ -0,0 +1,335 @@
+//! Poseidon hash over a prime field, natively and as a circuit gadget. Both sides run the same
+//! permutation with the same parameters, so an in-circuit hash always equals the native one.
+//!
+//! Round constants and the MDS matrix come from the Grain LFSR of the Poseidon reference
+//! implementation (`generate_parameters_grain.sage`). With the BN254 parameters from
+//! `Poseidon::circom` the outputs match circomlib's `Poseidon(n)` template.
+
+use std::collections::VecDeque;
+use std::sync::OnceLock;
+use num_bigint::BigInt;
+use crate::circuit::{Circuit, Wire};
+use crate::error::{Result, ZkError};
+use crate::field::{Bn254Fr, FieldElement, FieldParams};
+use crate::r1cs::{Operation, R1CS};
+
+/// Partial rounds circomlib uses for widths 2 to 17 with `alpha = 5` and 8 full rounds.
+const CIRCOM_PARTIAL_ROUNDS: [usize; 16] = [56, 57, 56, 60, 60, 63, 64, 63, 60, 66, 60, 65, 70, 60, 64, 68];
+const CIRCOM_FULL_ROUNDS: usize = 8;
+const CIRCOM_ALPHA: u64 = 5;
+
+/// A Poseidon instance: the permutation over `width` field elements and the sponge-free hash of
+/// `width - 1` inputs built on it.
+pub struct Poseidon<F: FieldParams = Bn254Fr> {
+    width: usize,
+    alpha: u64,
+    full_rounds: usize,
+    partial_rounds: usize,
+    /// `width` constants per round, round by round.
+    round_constants: Vec<FieldElement<F>>,
+    mds: Vec<Vec<FieldElement<F>>>,
+}
+
+impl<F: FieldParams> Poseidon<F> {
+    /// Generate the parameters for `x^alpha` S-boxes. The round numbers are not checked for security;
+    /// `alpha` must be coprime to `p - 1` for the S-box to be a permutation.
+    pub fn new(width: usize, alpha: u64, full_rounds: usize, partial_rounds: usize) -> Result<Self> {
+        if width < 2 {
+            return Err(ZkError::InvalidParameters("Poseidon needs a capacity element and at least one input".into()));
+        }
+        if !full_rounds.is_multiple_of(2) {
+            return Err(ZkError::InvalidParameters("full rounds are split evenly around the partial ones".into()));
+        }
+
+        let field_bits = FieldElement::<F>::modulus().bits() as usize;
+        let mut grain = Grain::new(field_bits, width, full_rounds, partial_rounds);
+        let round_constants = (0..(full_rounds + partial_rounds) * width)
+            .map(|_| grain.next_field_element::<F>(true))
+            .collect();
+
+        // A Cauchy matrix 1 / (x_i + y_j) from 2 * width distinct samples.
+        let mds = loop {
+            let samples: Vec<FieldElement<F>> =
+                (0..2 * width).map(|_| grain.next_field_element::<F>(false)).collect();
+            let distinct = samples.iter().enumerate().all(|(i, a)| samples[..i].iter().all(|b| a != b));
+            let (xs, ys) = samples.split_at(width);
+            if distinct && xs.iter().all(|x| ys.iter().all(|y| !(x + y).is_zero())) {
+                break xs.iter().map(|x| ys.iter().map(|y| (x + y).inv()).collect()).collect::<Result<_>>()?;
+            }
+        };
+
+        Ok(Poseidon { width, alpha, full_rounds, partial_rounds, round_constants, mds })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Apply the permutation to `state` in place.
+    pub fn permute(&self, state: &mut [FieldElement<F>]) -> Result<()> {
+        if state.len() != self.width {
+            return Err(ZkError::LengthMismatch { expected: self.width, actual: state.len() });
+        }
+
+        for round in 0..self.full_rounds + self.partial_rounds {
+            let constants = &self.round_constants[round * self.width..(round + 1) * self.width];
+            for (element, constant) in state.iter_mut().zip(constants) {
+                *element += constant;
+            }
+
+            let sboxes = if self.is_full_round(round) { self.width } else { 1 };
+            for element in &mut state[..sboxes] {
+                *element = element.pow(self.alpha);
+            }
+
+            let mixed: Vec<FieldElement<F>> = self
+                .mds
+                .iter()
+                .map(|row| row.iter().zip(state.iter()).map(|(m, s)| m * s).sum())
+                .collect();
+            state.copy_from_slice(&mixed);
+        }
+        Ok(())
+    }
+
+    /// Hash exactly `width - 1` elements: they fill the state after a zero capacity element, and the
+    /// first element of the permuted state is the digest.
+    pub fn hash(&self, inputs: &[FieldElement<F>]) -> Result<FieldElement<F>> {
+        self.check_arity(inputs.len())?;
+
+        let mut state = vec![FieldElement::zero()];
+        state.extend_from_slice(inputs);
+        self.permute(&mut state)?;
+        Ok(state[0])
+    }
+
+    /// The same hash as `hash`, computed in `cs`. Returns a private wire holding the digest.
+    pub fn hash_gadget(&self, cs: &mut Circuit<F>, inputs: &[Wire]) -> Result<Wire> {
+        self.synthesize(cs, inputs)
+    }
+
+    /// The same hash as `hash`, laid out directly as R1CS variables and `Operation::Hash` constraints.
+    /// Returns the index of the digest variable.
+    pub fn hash_r1cs(&self, r1cs: &mut R1CS<F>, inputs: &[usize]) -> Result<usize> {
+        self.synthesize(r1cs, inputs)
+    }
+
+    fn check_arity(&self, num_inputs: usize) -> Result<()> {
+        if num_inputs != self.width - 1 {
+            return Err(ZkError::LengthMismatch { expected: self.width - 1, actual: num_inputs });
+        }
+        Ok(())
+    }
+
+    fn is_full_round(&self, round: usize) -> bool {
+        let half = self.full_rounds / 2;
+        round < half || round >= half + self.partial_rounds
+    }
+
+    /// The permutation as constraints. State elements are carried as linear combinations and only
+    /// materialized where an S-box needs them and after each MDS mix, so adding round constants and
+    /// skipping S-boxes in partial rounds is free.
+    fn synthesize<S: ConstraintSink<F>>(&self, cs: &mut S, inputs: &[S::Var]) -> Result<S::Var> {
+        self.check_arity(inputs.len())?;
+
+        let one = FieldElement::one();
+        let mut state: Vec<Vec<(FieldElement<F>, S::Var)>> = vec![Vec::new()];
+        state.extend(inputs.iter().map(|input| vec![(one, *input)]));
+
+        for round in 0..self.full_rounds + self.partial_rounds {
+            let constants = &self.round_constants[round * self.width..(round + 1) * self.width];
+            for (element, constant) in state.iter_mut().zip(constants) {
+                element.push((*constant, S::one()));
+            }
+
+            let sboxes = if self.is_full_round(round) { self.width } else { 1 };
+            for element in &mut state[..sboxes] {
+                let base = cs.linear_combination(element)?;
+                *element = vec![(one, self.power(cs, base)?)];
+            }
+
+            state = self
+                .mds
+                .iter()
+                .map(|row| {
+                    let terms: Vec<(FieldElement<F>, S::Var)> = row
+                        .iter()
+                        .zip(&state)
+                        .flat_map(|(m, element)| element.iter().map(move |(coeff, var)| (m * coeff, *var)))
+                        .collect();
+                    Ok(vec![(one, cs.linear_combination(&terms)?)])
+                })
+                .collect::<Result<_>>()?;
+        }
+
+        Ok(state[0][0].1)
+    }
+
+    /// `base^alpha` by square-and-multiply.
+    fn power<S: ConstraintSink<F>>(&self, cs: &mut S, base: S::Var) -> Result<S::Var> {
+        let mut result = base;
+        for bit in (0..63 - self.alpha.leading_zeros()).rev() {
+            result = cs.mul(result, result)?;
+            if (self.alpha >> bit) & 1 == 1 {
+                result = cs.mul(result, base)?;
+            }
+        }
+        Ok(result)
+    }
+}
+
+impl Poseidon {
+    /// circomlib's BN254 parameters for hashing `num_inputs` elements (1 to 16). Generated once per
+    /// width and shared afterwards.
+    pub fn circom(num_inputs: usize) -> Result<&'static Poseidon> {
+        if !(1..=CIRCOM_PARTIAL_ROUNDS.len()).contains(&num_inputs) {
+            return Err(ZkError::InvalidParameters(format!("circomlib Poseidon takes 1 to 16 inputs, not {num_inputs}")));
+        }
+        Ok(circom_instance(num_inputs))
+    }
+}
+
+/// circomlib-compatible Poseidon hash of 1 to 16 BN254 elements. The arity is checked at compile
+/// time, so unlike `Poseidon::hash` this cannot fail.
+pub fn hash<const N: usize>(inputs: &[FieldElement; N]) -> FieldElement {
+    const { assert!(N >= 1 && N <= 16, "circomlib Poseidon takes 1 to 16 inputs") };
+    match circom_instance(N).hash(inputs) {
+        Ok(digest) => digest,
+        Err(_) => unreachable!("the arity matches the instance"),
+    }
+}
+
+/// In-circuit counterpart of `hash`.
+pub fn hash_gadget<const N: usize>(cs: &mut Circuit, inputs: &[Wire; N]) -> Wire {
+    const { assert!(N >= 1 && N <= 16, "circomlib Poseidon takes 1 to 16 inputs") };
+    match circom_instance(N).synthesize(cs, inputs) {
+        Ok(digest) => digest,
+        Err(_) => unreachable!("the arity matches and circuit gates cannot fail"),
+    }
+}
+
+/// `Poseidon::circom` for an arity already known to be in range.
+fn circom_instance(num_inputs: usize) -> &'static Poseidon {
+    static INSTANCES: [OnceLock<Poseidon>; 16] = [const { OnceLock::new() }; 16];
+
+    INSTANCES[num_inputs - 1].get_or_init(|| {
+        let partial_rounds = CIRCOM_PARTIAL_ROUNDS[num_inputs - 1];
+        Poseidon::new(num_inputs + 1, CIRCOM_ALPHA, CIRCOM_FULL_ROUNDS, partial_rounds)
+            .expect("circomlib parameters are valid")
+    })
+}
+
+/// Where `Poseidon::synthesize` puts its constraints, so `Circuit` and `R1CS` share one layout.
+trait ConstraintSink<F: FieldParams> {
+    type Var: Copy;
+
+    fn one() -> Self::Var;
+    fn mul(&mut self, a: Self::Var, b: Self::Var) -> Result<Self::Var>;
+    fn linear_combination(&mut self, terms: &[(FieldElement<F>, Self::Var)]) -> Result<Self::Var>;
+}
+
+impl<F: FieldParams> ConstraintSink<F> for Circuit<F> {
+    type Var = Wire;
+
+    fn one() -> Wire {
+        Wire::ONE
+    }
+
+    fn mul(&mut self, a: Wire, b: Wire) -> Result<Wire> {
+        Ok(Circuit::mul(self, a, b))
+    }
+
+    fn linear_combination(&mut self, terms: &[(FieldElement<F>, Wire)]) -> Result<Wire> {
+        Ok(Circuit::linear_combination(self, terms))
+    }
+}
+
+impl<F: FieldParams> ConstraintSink<F> for R1CS<F> {
+    type Var = usize;
+
+    fn one() -> usize {
+        R1CS::<F>::ONE
+    }
+
+    fn mul(&mut self, a: usize, b: usize) -> Result<usize> {
+        let one = FieldElement::one();
+        let output = self.add_variable(self.value(a)? * self.value(b)?);
+        self.add_constraint(&[(a, one)], &[(b, one)], &[(output, one)])?;
+        self.mark_last_constraint(Operation::Hash);
+        Ok(output)
+    }
+
+    fn linear_combination(&mut self, terms: &[(FieldElement<F>, usize)]) -> Result<usize> {
+        let value = terms.iter().map(|(coeff, index)| Ok(*coeff * self.value(*index)?)).sum::<Result<_>>()?;
+        let output = self.add_variable(value);
+        let terms: Vec<(usize, FieldElement<F>)> = terms.iter().map(|(coeff, index)| (*index, *coeff)).collect();
+        self.add_linear_constraint(&terms, output)?;
+        self.mark_last_constraint(Operation::Hash);
+        Ok(output)
+    }
+}
+
+/// The Grain LFSR the Poseidon reference implementation draws its parameters from. It is seeded with
+/// the field type (prime), the S-box type (`x^alpha`), the field size, the width and the round numbers.
+struct Grain {
+    state: VecDeque<bool>,
+    field_bits: usize,
+}
+
+impl Grain {
+    fn new(field_bits: usize, width: usize, full_rounds: usize, partial_rounds: usize) -> Self {
+        let mut state = VecDeque::with_capacity(80);
+        let mut push = |value: usize, bits: usize| {
+            for bit in (0..bits).rev() {
+                state.push_back((value >> bit) & 1 == 1);
+            }
+        };
+        push(1, 2);
+        push(0, 4);
+        push(field_bits, 12);
+        push(width, 12);
+        push(full_rounds, 10);
+        push(partial_rounds, 10);
+        push((1 << 30) - 1, 30);
+
+        let mut grain = Grain { state, field_bits };
+        for _ in 0..160 {
+            grain.step();
+        }
+        grain
+    }
+
+    fn step(&mut self) -> bool {
+        let s = &self.state;
+        let bit = s[62] ^ s[51] ^ s[38] ^ s[23] ^ s[13] ^ s[0];
+        self.state.pop_front();
+        self.state.push_back(bit);
+        bit
+    }
+
+    /// Bits are drawn in pairs and the second one is kept only when the first is set.
+    fn next_bit(&mut self) -> bool {
+        loop {
+            let keep = self.step();
+            let bit = self.step();
+            if keep {
+                return bit;
+            }
+        }
+    }
+
+    /// `field_bits` bits read as a big-endian integer. Round constants resample until the value is
+    /// below the modulus; MDS samples are reduced instead.
+    fn next_field_element<F: FieldParams>(&mut self, reject_above_modulus: bool) -> FieldElement<F> {
+        let modulus = FieldElement::<F>::modulus();
+        loop {
+            let mut value = BigInt::from(0);
+            for _ in 0..self.field_bits {
+                value = (value << 1) + u8::from(self.next_bit());
+            }
+            if !reject_above_modulus || value < modulus {
+                return FieldElement::new(value);
+            }
+        }
+    }
+}
@@ -1,91 +1,133 @@
-use std::fs::File;
-use std::io;
-use std::io::Write;
+use ark_std::rand::{CryptoRng, RngCore};
 use num_bigint::BigInt;
-use num_traits::Zero;
+use rand::rngs::OsRng;
 use serde::{Deserialize, Serialize};
-use crate::field::FieldElement;
-use crate::r1cs::{Operation, R1CS};
+use crate::circuit::Circuit;
+use crate::error::{Result, ZkError};
+use crate::field::{Bn254Fr, FieldElement};
+use crate::format::{self, Header, PROOF_MAGIC};
+use crate::groth16::{self, Groth16Proof, ProvingKey, VerifyingKey};
+use crate::r1cs::R1CS;
+use crate::setup;
 
+/// A Groth16 proof together with the public inputs it was generated for. The private part of the
+/// witness never leaves the prover.
 #[derive(Serialize, Deserialize)]
 pub struct Proof {
-    pub witness: Vec<BigInt>, 
-    pub commitment: BigInt,    
+    pub public_inputs: Vec<BigInt>,
+    pub proof: Vec<u8>,
 }
 
-impl Proof {
-    // Generate a proof from R1CS and witness
-    pub fn generate_proof(_r1cs: &R1CS, witness: &Vec<FieldElement>) -> Proof {
-        // Create a commitment based on the witness
-        let mut commitment_input = BigInt::zero();
-        let witness_bigint: Vec<BigInt> = witness.iter().map(|w| w.get_value()).collect(); // Convert to Vec<BigInt>
+/// Everything `prove` produces, held in memory. Keys and the proof are in the compressed arkworks
+/// encoding `verify` takes. Nothing is written to disk unless the caller saves a part of it to a
+/// path of their choosing.
+pub struct ProvingArtifacts {
+    pub r1cs: R1CS,
+    pub proving_key: Vec<u8>,
+    pub verifying_key: Vec<u8>,
+    /// The public wires of the circuit, in R1CS order and without the constant one.
+    pub public_inputs: Vec<FieldElement>,
+    pub proof: Vec<u8>,
+}
 
-        for w in &witness_bigint {
-            commitment_input += w; // Use the BigInt value directly
-        }
+/// Run a fresh circuit-specific setup for `circuit` and prove its current wire values. The setup
+/// randomness comes from the OS and is dropped before this returns.
+pub fn prove(circuit: &Circuit) -> Result<ProvingArtifacts> {
+    prove_r1cs(circuit.to_r1cs()?)
+}
+
+/// `prove` for a constraint system that already carries its witness, such as one loaded with
+/// `iden3::read_circuit`.
+pub fn prove_r1cs(r1cs: R1CS) -> Result<ProvingArtifacts> {
+    let (pk, vk) = groth16::setup(&r1cs, &mut OsRng)?;
 
-        let commitment = Self::hash(&commitment_input, &BigInt::zero()); // Simplified hash, use appropriate values
+    let witness = r1cs.generate_witness();
+    let proof = groth16::prove(&pk, &r1cs, &witness, &mut OsRng)?;
 
+    Ok(ProvingArtifacts {
+        proving_key: groth16::to_bytes(&pk)?,
+        verifying_key: groth16::to_bytes(&vk)?,
+        public_inputs: r1cs.public_inputs(&witness)?.to_vec(),
+        proof: groth16::to_bytes(&proof)?,
+        r1cs,
+    })
+}
+
+/// Check an encoded proof against an encoded verifying key. Buffers that do not decode make the
+/// proof invalid.
+pub fn verify(vk: &[u8], public_inputs: &[FieldElement], proof: &[u8]) -> bool {
+    let (Ok(vk), Ok(proof)) = (groth16::from_bytes::<VerifyingKey>(vk), groth16::from_bytes::<Groth16Proof>(proof)) else {
+        return false;
+    };
+    groth16::verify(&vk, public_inputs, &proof)
+}
+
+impl ProvingArtifacts {
+    /// The proof and its public inputs, in the form `Proof::save_to_binary` writes.
+    pub fn to_proof(&self) -> Proof {
         Proof {
-            witness: witness_bigint, // Assign the converted Vec<BigInt>
-            commitment,
+            public_inputs: self.public_inputs.iter().map(|value| value.get_value()).collect(),
+            proof: self.proof.clone(),
         }
     }
 
+    pub fn save_proof(&self, filename: &str) -> Result<()> {
+        self.to_proof().save_to_binary(filename)
+    }
+
+    /// Written in the versioned key format `setup::load_proving_key` reads.
+    pub fn save_proving_key(&self, filename: &str) -> Result<()> {
+        let header = Header::new::<Bn254Fr>(setup::PROVING_KEY_MAGIC, 0, self.r1cs.variables.len());
+        format::write_file(filename, header, &self.proving_key)
+    }
 
-    pub fn save_to_binary(&self, filename: &str) -> io::Result<()> {
-        let mut file = File::create(filename)?;
-        let encoded: Vec<u8> = bincode::serialize(self).expect("Failed to serialize proof");
-        file.write_all(&encoded)?;
-        Ok(())
+    pub fn save_verifying_key(&self, filename: &str) -> Result<()> {
+        let header = Header::new::<Bn254Fr>(setup::VERIFYING_KEY_MAGIC, 0, self.r1cs.num_public());
+        format::write_file(filename, header, &self.verifying_key)
     }
+}
 
-    pub fn verify_proof(proof: &Proof, r1cs: &R1CS) -> bool {
-        // Check if the commitment matches the expected hash
-        let mut commitment_input = BigInt::zero();
-        for w in &proof.witness {
-            commitment_input += w; // Combine witness values
-        }
-        let expected_commitment = Self::hash(&commitment_input, &BigInt::zero()); // Use the same hash function
+impl Proof {
+    // Generate a proof from R1CS and witness
+    pub fn generate_proof<R: RngCore + CryptoRng>(
+        r1cs: &R1CS,
+        pk: &ProvingKey,
+        witness: &[FieldElement],
+        rng: &mut R,
+    ) -> Result<Proof> {
+        let proof = groth16::prove(pk, r1cs, witness, rng)?;
 
-        if proof.commitment != expected_commitment {
-            return false; // Commitment mismatch
-        }
-        for constraint in &r1cs.constraints {
-            let left_eval = constraint.left.iter().map(|(var, coeff)| {
-                var.value.clone() * coeff // This produces FieldElement
-            }).map(|fe| fe.get_value()).sum::<BigInt>(); // Convert to BigInt and sum
-
-            let right_eval = constraint.right.iter().map(|(var, coeff)| {
-                var.value.clone() * coeff
-            }).map(|fe| fe.get_value()).sum::<BigInt>();
-
-            let output_eval = constraint.output.iter().map(|(var, coeff)| {
-                var.value.clone() * coeff
-            }).map(|fe| fe.get_value()).sum::<BigInt>();
-
-            // Verify the specific operation
-            match constraint.operation {
-                Operation::Add => {
-                    if left_eval + right_eval != output_eval {
-                        return false; // Constraint not satisfied
-                    }
-                },
-                Operation::Mul => {
-                    if left_eval * right_eval != output_eval {
-                        return false; // Constraint not satisfied
-                    }
-                },
-                Operation::Hash => todo!(),
-            }
-        }
+        Ok(Proof {
+            public_inputs: r1cs.public_inputs(witness)?.iter().map(|w| w.get_value()).collect(),
+            proof: groth16::to_bytes(&proof)?,
+        })
+    }
+
+    /// Written under a `format::Header` that counts the public inputs.
+    pub fn save_to_binary(&self, filename: &str) -> Result<()> {
+        let body = bincode::serialize(self).map_err(|error| ZkError::Serialization(error.to_string()))?;
+        format::write_file(filename, Header::new::<Bn254Fr>(PROOF_MAGIC, 0, self.public_inputs.len()), &body)
+    }
 
-        true 
+    /// Read a file written by `save_to_binary`, rejecting anything but a BN254 proof whose body
+    /// matches its header.
+    pub fn load_from_binary(filename: &str) -> Result<Proof> {
+        let (header, body) = format::read_file(filename, PROOF_MAGIC)?;
+        header.check_field::<Bn254Fr>()?;
+        let proof: Proof = bincode::deserialize(&body).map_err(|error| ZkError::Deserialization(error.to_string()))?;
+        header.check_counts(0, proof.public_inputs.len())?;
+        Ok(proof)
     }
 
-    fn hash(left: &BigInt, right: &BigInt) -> BigInt {
+    /// A proof that cannot be decoded is treated as invalid.
+    pub fn verify_proof(proof: &Proof, vk: &VerifyingKey) -> bool {
+        let Ok(groth16_proof) = groth16::from_bytes::<Groth16Proof>(&proof.proof) else {
+            return false;
+        };
+        let public_inputs: Vec<FieldElement> = proof.public_inputs.iter()
+            .map(|value| FieldElement::new(value.clone()))
+            .collect();
 
-        let combined = left + right; 
-        combined % BigInt::from(1_000_000_007)
+        groth16::verify(vk, &public_inputs, &groth16_proof)
     }
 }
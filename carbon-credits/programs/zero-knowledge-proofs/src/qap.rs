@@ -1,106 +1,111 @@
-use num_bigint::BigInt;
 use serde::{Serialize, Deserialize};
-use std::collections::HashMap;
-use num_traits::Zero;
-use crate::field::FieldElement;
-
+use crate::error::{Result, ZkError};
+use crate::field::{Bn254Fr, FieldElement, FieldParams};
+use crate::poly::{EvaluationDomain, Polynomial};
+use crate::r1cs::{Variable, R1CS};
+
+/// A sparse matrix row: `(variable index, coefficient)` pairs.
+type Row<F> = Vec<(usize, FieldElement<F>)>;
+
+/// The quadratic arithmetic program of an R1CS. Constraint `j` is pinned to the domain point `w^j`,
+/// and the polynomials `A_i(x)`, `B_i(x)` and `C_i(x)` of variable `i` interpolate its coefficients in
+/// the A, B and C matrices over those points. A witness satisfies the R1CS exactly when
+/// `A(x) * B(x) - C(x)` is divisible by `Z(x) = x^n - 1`, the vanishing polynomial of the domain.
+///
+/// The matrices are kept sparse and the polynomials are only materialized on request, so building the
+/// quotient for a witness costs `O(n log n)` on top of one pass over the constraints.
 #[derive(Clone, Serialize, Deserialize)]
-pub struct Variable {
-    pub index: usize,
-    pub value: BigInt,
-}
-
-#[derive(Serialize, Deserialize)]
-pub struct Polynomial {
-    coefficients: HashMap<usize, FieldElement>, // Coefficients keyed by variable index
-}
-
-#[derive(Serialize, Deserialize)]
-pub struct QAP {
-    pub left: Polynomial,
-    pub right: Polynomial,
-    pub output: Polynomial,
+#[serde(bound = "")]
+pub struct QAP<F: FieldParams = Bn254Fr> {
+    pub domain: EvaluationDomain<F>,
+    num_variables: usize,
+    left: Vec<Row<F>>,
+    right: Vec<Row<F>>,
+    output: Vec<Row<F>>,
 }
 
-impl QAP {
-    pub fn new() -> Self {
-        QAP {
-            left: Polynomial::new(),
-            right: Polynomial::new(),
-            output: Polynomial::new(),
-        }
+/// `(A(x), B(x), C(x))` for one witness.
+pub type WitnessPolynomials<F> = (Polynomial<F>, Polynomial<F>, Polynomial<F>);
+
+type WitnessPolynomialValues<F> = (Vec<FieldElement<F>>, Vec<FieldElement<F>>, Vec<FieldElement<F>>);
+
+impl<F: FieldParams> QAP<F> {
+    /// Fails with `ZkError::DomainSize` if the field has too few roots of unity for the constraints.
+    pub fn from_r1cs(r1cs: &R1CS<F>) -> Result<Self> {
+        let domain = EvaluationDomain::new(r1cs.constraints.len())?;
+        let row = |terms: &[(Variable<F>, FieldElement<F>)]| -> Row<F> {
+            terms.iter().map(|(variable, coeff)| (variable.index, *coeff)).collect()
+        };
+        Ok(QAP {
+            domain,
+            num_variables: r1cs.variables.len(),
+            left: r1cs.constraints.iter().map(|constraint| row(&constraint.left)).collect(),
+            right: r1cs.constraints.iter().map(|constraint| row(&constraint.right)).collect(),
+            output: r1cs.constraints.iter().map(|constraint| row(&constraint.output)).collect(),
+        })
     }
 
-    pub fn add_constraint(&mut self, left_coeffs: &[(usize, FieldElement)], right_coeffs: &[(usize, FieldElement)], output_coeffs: &[(usize, FieldElement)], _modulus: &BigInt) {
-        for (index, coeff) in left_coeffs {
-            *self.left.coefficients.entry(*index).or_insert(FieldElement::new(BigInt::zero())) += coeff.clone();
-        }
-        for (index, coeff) in right_coeffs {
-            *self.right.coefficients.entry(*index).or_insert(FieldElement::new(BigInt::zero())) += coeff.clone();
-        }
-        for (index, coeff) in output_coeffs {
-            *self.output.coefficients.entry(*index).or_insert(FieldElement::new(BigInt::zero())) += coeff.clone();
-        }
+    pub fn num_variables(&self) -> usize {
+        self.num_variables
     }
 
-    pub fn evaluate(&self, assignment: &Vec<FieldElement>) -> FieldElement {
-        let left_eval = self.left.evaluate(assignment);
-        let right_eval = self.right.evaluate(assignment);
-        let output_eval = self.output.evaluate(assignment);
-
-        // Return the evaluation result: left * right - output
-        left_eval.mul(&right_eval).sub(&output_eval)
+    pub fn vanishing_polynomial(&self) -> Polynomial<F> {
+        self.domain.vanishing_polynomial()
     }
-}
 
-impl Polynomial {
-    pub fn new() -> Self {
-        Polynomial { coefficients: HashMap::new() }
+    /// `(A_i(x), B_i(x), C_i(x))` for variable `index`.
+    pub fn variable_polynomials(&self, index: usize) -> Result<WitnessPolynomials<F>> {
+        if index >= self.num_variables {
+            return Err(ZkError::InvalidWire(index));
+        }
+        let column = |rows: &[Row<F>]| {
+            let evaluations: Vec<FieldElement<F>> = rows
+                .iter()
+                .map(|row| row.iter().filter(|(variable, _)| *variable == index).map(|(_, coeff)| *coeff).sum())
+                .collect();
+            self.domain.interpolate(&evaluations)
+        };
+        Ok((column(&self.left)?, column(&self.right)?, column(&self.output)?))
     }
 
-/*************  ✨ Codeium Command ⭐  *************/
-/******  13b838ed-c5ce-4c60-be1b-60728fd1fe5d  *******/
-    pub fn add_term(&mut self, index: usize, coefficient: FieldElement) {
-        self.coefficients.insert(index, coefficient);
+    /// `A(x)`, `B(x)` and `C(x)` for a witness, i.e. `sum w_i * A_i(x)` and so on.
+    pub fn witness_polynomials(&self, witness: &[FieldElement<F>]) -> Result<WitnessPolynomials<F>> {
+        let (a, b, c) = self.evaluations(witness)?;
+        Ok((self.domain.interpolate(&a)?, self.domain.interpolate(&b)?, self.domain.interpolate(&c)?))
     }
 
-    pub fn evaluate(&self, assignment: &Vec<FieldElement>) -> FieldElement {
-        let mut result = FieldElement::new(BigInt::zero()); // Use the same modulus
-        for (index, coefficient) in &self.coefficients {
-            result = result.add(&coefficient.mul(&assignment[*index]));
+    /// `H(x) = (A(x) * B(x) - C(x)) / Z(x)`, or `ZkError::Unsatisfied` if the witness does not satisfy
+    /// the constraints.
+    ///
+    /// `A * B - C` vanishes on the domain exactly when every constraint holds, which is when `Z`
+    /// divides it. `H` then has degree below `n`, so it is recovered from its values on a coset of the
+    /// domain, where `Z` is the nonzero constant `offset^n - 1`.
+    pub fn quotient(&self, witness: &[FieldElement<F>]) -> Result<Polynomial<F>> {
+        let (a, b, c) = self.evaluations(witness)?;
+        if a.iter().zip(&b).zip(&c).any(|((a, b), c)| a * b != *c) {
+            return Err(ZkError::Unsatisfied);
         }
-        result
-    }
-
-    /// Perform Lagrange interpolation to find a polynomial that passes through all given points.
-    pub fn interpolate(points: &[(FieldElement, FieldElement)], _modulus: &BigInt) -> Polynomial {
-        let mut result = Polynomial::new();
-
-        for (i, &(ref x_i, ref y_i)) in points.iter().enumerate() {
-            // Start with y_i
-            let mut term = vec![(0, y_i.clone())];
 
-            // Compute the Lagrange basis polynomial L_i(x)
-            for (j, &(ref x_j, _)) in points.iter().enumerate() {
-                if i != j {
-                    let denom = x_i.sub(x_j).inv();
-                    let negated_x_j = x_j.negate();
-                    let coeff = denom.mul(&negated_x_j);
-
-                    term.push((1, denom)); // L_i(x) = product (x - x_j) / (x_i - x_j)
-
-                    for k in 0..term.len() {
-                        term[k] = (term[k].0, term[k].1.mul(&coeff));
-                    }
-                }
-            }
+        let on_coset = |evaluations: &[FieldElement<F>]| self.domain.coset_fft(&self.domain.ifft(evaluations)?);
+        let (a, b, c) = (on_coset(&a)?, on_coset(&b)?, on_coset(&c)?);
+        let vanishing_inv = self.domain.evaluate_vanishing(&self.domain.coset_offset()).inv()?;
+        let quotient: Vec<FieldElement<F>> =
+            a.iter().zip(&b).zip(&c).map(|((a, b), c)| (a * b - c) * vanishing_inv).collect();
+        Ok(Polynomial::new(self.domain.coset_ifft(&quotient)?))
+    }
 
-            // Add L_i(x) * y_i to the result
-            for (index, coeff) in term.iter() {
-                *result.coefficients.entry(*index).or_insert(FieldElement::new(BigInt::zero())) += coeff.clone();
-            }
+    /// `A(w^j)`, `B(w^j)` and `C(w^j)` over the domain: the constraint rows applied to the witness,
+    /// padded with zeroes.
+    fn evaluations(&self, witness: &[FieldElement<F>]) -> Result<WitnessPolynomialValues<F>> {
+        if witness.len() != self.num_variables {
+            return Err(ZkError::LengthMismatch { expected: self.num_variables, actual: witness.len() });
         }
-
-        result
+        let evaluate = |rows: &[Row<F>]| {
+            let mut values: Vec<FieldElement<F>> =
+                rows.iter().map(|row| row.iter().map(|(variable, coeff)| *coeff * witness[*variable]).sum()).collect();
+            values.resize(self.domain.size(), FieldElement::zero());
+            values
+        };
+        Ok((evaluate(&self.left), evaluate(&self.right), evaluate(&self.output)))
     }
 }
@@ -1,18 +1,21 @@
-use crate::field::FieldElement;
-use num_bigint::BigInt;
+use crate::field::{Bn254Fr, FieldElement, FieldParams};
 use serde::{Serialize, Deserialize};
-use std::fs::File;
-use std::io::{Write};
-use num_traits::Zero;
+use ark_std::rand::{CryptoRng, RngCore};
+use crate::error::{Result, ZkError};
+use crate::format::{self, Header, R1CS_MAGIC};
+use crate::groth16::ProvingKey;
+use crate::poseidon::Poseidon;
 use crate::proof::Proof;
 use crate::qap::QAP;
 
 #[derive(Clone, Serialize, Deserialize)]
-pub struct Variable {
+#[serde(bound = "")]
+pub struct Variable<F: FieldParams = Bn254Fr> {
     pub index: usize,
-    pub value: FieldElement,
+    pub value: FieldElement<F>,
 }
 
+/// What a constraint was emitted for. Only informational; every constraint is checked the same way.
 #[derive(Serialize, Deserialize)]
 pub enum Operation {
     Add,
@@ -21,97 +24,212 @@ pub enum Operation {
 }
 
 #[derive(Serialize, Deserialize)]
-pub struct Constraint {
-    pub left: Vec<(Variable, BigInt)>,
-    pub right: Vec<(Variable, BigInt)>,
-    pub output: Vec<(Variable, BigInt)>,
+#[serde(bound = "")]
+pub struct Constraint<F: FieldParams = Bn254Fr> {
+    pub left: Vec<(Variable<F>, FieldElement<F>)>,
+    pub right: Vec<(Variable<F>, FieldElement<F>)>,
+    pub output: Vec<(Variable<F>, FieldElement<F>)>,
     pub operation: Operation,
 }
 
+/// A rank-1 constraint system. Variables are laid out as the constant one, then the public inputs,
+/// then the private wires; the verifier only ever sees the first `num_public` values after the one.
 #[derive(Serialize, Deserialize)]
-pub struct R1CS {
-    pub variables: Vec<Variable>,
-    pub constraints: Vec<Constraint>,
-    pub qap: QAP,
+#[serde(bound = "")]
+pub struct R1CS<F: FieldParams = Bn254Fr> {
+    pub variables: Vec<Variable<F>>,
+    pub constraints: Vec<Constraint<F>>,
+    num_public: usize,
 }
 
+impl<F: FieldParams> Default for R1CS<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Groth16 runs over BN254, so only constraint systems over its scalar field can be proven.
 impl R1CS {
+    pub fn generate_proof<R: RngCore + CryptoRng>(&self, pk: &ProvingKey, witness: &[FieldElement], rng: &mut R) -> Result<Proof> {
+        Proof::generate_proof(self, pk, witness, rng)
+    }
+
+    /// Poseidon hash of `inputs` (circomlib parameters), as new variables and `Operation::Hash`
+    /// constraints. Returns the index of the digest variable.
+    pub fn add_hash(&mut self, inputs: &[usize]) -> Result<usize> {
+        Poseidon::circom(inputs.len())?.hash_r1cs(self, inputs)
+    }
+}
+
+impl<F: FieldParams> R1CS<F> {
+    /// Index of the variable that always holds 1, so constraints can carry constant terms.
+    pub const ONE: usize = 0;
+
     pub fn new() -> Self {
         R1CS {
-            variables: Vec::new(),
+            variables: vec![Variable { index: Self::ONE, value: FieldElement::one() }],
             constraints: Vec::new(),
-            qap: QAP::new(), // Initialize QAP
+            num_public: 0,
         }
     }
 
-    pub fn add_constraint(&mut self, left_coeffs: &[(usize, FieldElement)], right_coeffs: &[(usize, FieldElement)], output_coeffs: &[(usize, FieldElement)], modulus: &BigInt) {
-        self.qap.add_constraint(left_coeffs, right_coeffs, output_coeffs, modulus);
+    pub fn num_public(&self) -> usize {
+        self.num_public
     }
 
-    /// Generates a witness based on the variable values.
-    pub fn generate_witness(&self) -> Vec<FieldElement> {
-        self.variables.iter().map(|var| {
-            var.value.clone() 
-        }).collect()
+    /// Add a public input. They come right after the constant one, so all of them must be added
+    /// before the first private variable.
+    pub fn add_public_input(&mut self, value: FieldElement<F>) -> Result<usize> {
+        if self.variables.len() != self.num_public + 1 {
+            return Err(ZkError::InvalidParameters("public inputs must be added before private variables".into()));
+        }
+        self.num_public += 1;
+        Ok(self.add_variable(value))
+    }
+
+    /// The public part of `witness`, in order and without the constant one.
+    pub fn public_inputs<'a>(&self, witness: &'a [FieldElement<F>]) -> Result<&'a [FieldElement<F>]> {
+        if witness.len() != self.variables.len() {
+            return Err(ZkError::LengthMismatch { expected: self.variables.len(), actual: witness.len() });
+        }
+        Ok(&witness[1..=self.num_public])
+    }
+
+    /// Fails with `ZkError::InvalidWire` if a term refers to a variable that does not exist yet.
+    pub fn add_constraint(&mut self, left_coeffs: &[(usize, FieldElement<F>)], right_coeffs: &[(usize, FieldElement<F>)], output_coeffs: &[(usize, FieldElement<F>)]) -> Result<()> {
+        let terms = |coeffs: &[(usize, FieldElement<F>)]| -> Result<Vec<(Variable<F>, FieldElement<F>)>> {
+            coeffs
+                .iter()
+                .map(|(index, coeff)| {
+                    let variable = self.variables.get(*index).ok_or(ZkError::InvalidWire(*index))?;
+                    Ok((variable.clone(), *coeff))
+                })
+                .collect()
+        };
+        let constraint = Constraint {
+            left: terms(left_coeffs)?,
+            right: terms(right_coeffs)?,
+            output: terms(output_coeffs)?,
+            operation: Operation::Mul,
+        };
+        self.constraints.push(constraint);
+        Ok(())
+    }
+
+    /// `(sum of terms) * 1 = output`, the shape additions and other linear gates take once lowered.
+    pub fn add_linear_constraint(&mut self, terms: &[(usize, FieldElement<F>)], output: usize) -> Result<()> {
+        let one = FieldElement::one();
+        self.add_constraint(terms, &[(Self::ONE, one)], &[(output, one)])?;
+        self.mark_last_constraint(Operation::Add);
+        Ok(())
     }
 
+    pub(crate) fn mark_last_constraint(&mut self, operation: Operation) {
+        if let Some(constraint) = self.constraints.last_mut() {
+            constraint.operation = operation;
+        }
+    }
+
+    /// Value assigned to variable `index`.
+    pub fn value(&self, index: usize) -> Result<FieldElement<F>> {
+        self.variables.get(index).map(|variable| variable.value).ok_or(ZkError::InvalidWire(index))
+    }
+
+    /// Replace every variable value with the matching entry of `witness`, which must cover all of them,
+    /// constant one included.
+    pub fn assign_witness(&mut self, witness: &[FieldElement<F>]) -> Result<()> {
+        if witness.len() != self.variables.len() {
+            return Err(ZkError::LengthMismatch { expected: self.variables.len(), actual: witness.len() });
+        }
+        for (variable, value) in self.variables.iter_mut().zip(witness) {
+            variable.value = *value;
+        }
+        for constraint in &mut self.constraints {
+            for (variable, _) in constraint.left.iter_mut().chain(&mut constraint.right).chain(&mut constraint.output) {
+                variable.value = witness[variable.index];
+            }
+        }
+        Ok(())
+    }
 
-    pub fn generate_proof(&self, witness: &Vec<FieldElement>) -> Proof {
-        Proof::generate_proof(self, witness) 
+    /// Generates a witness based on the variable values.
+    pub fn generate_witness(&self) -> Vec<FieldElement<F>> {
+        self.variables.iter().map(|var| var.value).collect()
     }
 
 
-    pub fn evaluate_qap(&self) -> BigInt {
-        let witness = self.generate_witness(); 
-        let result = self.qap.evaluate(&witness); 
-        result.get_value() 
+    /// Reduce the constraints to a QAP, e.g. to compute the quotient polynomial of a witness.
+    pub fn to_qap(&self) -> Result<QAP<F>> {
+        QAP::from_r1cs(self)
     }
 
 
-    pub fn add_variable(&mut self, value: FieldElement) -> usize {
+    /// Add a private variable.
+    pub fn add_variable(&mut self, value: FieldElement<F>) -> usize {
         let index = self.variables.len();
         self.variables.push(Variable { index, value });
         index
     }
 
 
-    pub fn save_to_binary(&self, filename: &str) {
-        let mut file = File::create(filename).expect("Could not create proof file");
-        let encoded: Vec<u8> = bincode::serialize(&self).expect("Failed to serialize proof");
-        file.write_all(&encoded).expect("Failed to write proof to file");
+    /// Write the constraint system under a `format::Header` that records its field and size.
+    pub fn save_to_binary(&self, filename: &str) -> Result<()> {
+        let body = bincode::serialize(self).map_err(|error| ZkError::Serialization(error.to_string()))?;
+        let header = Header::new::<F>(R1CS_MAGIC, self.constraints.len(), self.variables.len());
+        format::write_file(filename, header, &body)
     }
 
-
-    pub fn load_from_binary(filename: &str) -> Self {
-        let file = File::open(filename).expect("Could not open file");
-        let r1cs: R1CS = bincode::deserialize_from(file).expect("Failed to deserialize R1CS");
-        r1cs
+    /// Read a file written by `save_to_binary`. Fails with `ZkError::ModulusMismatch` if it was saved
+    /// over a different field, with `ZkError::ChecksumMismatch` if it was altered since, and with
+    /// `ZkError::InvalidWire` if a constraint refers to a variable the system does not have.
+    pub fn load_from_binary(filename: &str) -> Result<Self> {
+        let (header, body) = format::read_file(filename, R1CS_MAGIC)?;
+        header.check_field::<F>()?;
+        let r1cs: R1CS<F> =
+            bincode::deserialize(&body).map_err(|error| ZkError::Deserialization(error.to_string()))?;
+        header.check_counts(r1cs.constraints.len(), r1cs.variables.len())?;
+        if r1cs.num_public >= r1cs.variables.len() {
+            return Err(ZkError::Deserialization(format!("{} public inputs but {} variables", r1cs.num_public, r1cs.variables.len())));
+        }
+        if let Some((position, variable)) = r1cs.variables.iter().enumerate().find(|(position, variable)| variable.index != *position) {
+            return Err(ZkError::Deserialization(format!("variable {position} is numbered {}", variable.index)));
+        }
+        let terms = r1cs.constraints.iter().flat_map(|constraint| constraint.left.iter().chain(&constraint.right).chain(&constraint.output));
+        for (variable, _) in terms {
+            if variable.index >= r1cs.variables.len() {
+                return Err(ZkError::InvalidWire(variable.index));
+            }
+        }
+        Ok(r1cs)
     }
 
-    pub fn verify_witness(&self, witness: &[FieldElement]) -> bool {
+    /// Check `<A, w> * <B, w> = <C, w>` for every constraint.
+    pub fn verify_witness(&self, witness: &[FieldElement<F>]) -> bool {
+        if witness.len() != self.variables.len() {
+            return false;
+        }
         for constraint in &self.constraints {
-            let mut left_eval = FieldElement::new(BigInt::zero());
-            let mut right_eval = FieldElement::new(BigInt::zero());
+            let mut left_eval = FieldElement::zero();
+            let mut right_eval = FieldElement::zero();
 
             for (var_index, coeff) in &constraint.left {
-                let var_value = &witness[var_index.index];
-                left_eval = left_eval + (var_value.clone() * coeff);
+                let var_value = witness[var_index.index];
+                left_eval += var_value * *coeff;
             }
 
             for (var_index, coeff) in &constraint.right {
-                let var_value = &witness[var_index.index]; 
-                right_eval = right_eval + (var_value.clone() * coeff); 
+                let var_value = witness[var_index.index];
+                right_eval += var_value * *coeff;
             }
 
-            let mut output_eval = FieldElement::new(BigInt::zero());
+            let mut output_eval = FieldElement::zero();
             for (var_index, coeff) in &constraint.output {
-                let var_value = &witness[var_index.index]; 
-                output_eval = output_eval + (var_value.clone() * coeff);
+                let var_value = witness[var_index.index];
+                output_eval += var_value * *coeff;
             }
 
-
-            if left_eval != right_eval || right_eval != output_eval {
-                return false; 
+            if left_eval * right_eval != output_eval {
+                return false;
             }
         }
         true
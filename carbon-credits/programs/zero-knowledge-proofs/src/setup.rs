@@ -0,0 +1,444 @@
+//! Groth16 parameter generation in two phases. The first, Powers of Tau, is circuit-independent and
+//! is taken from a public multi-party ceremony such as the Hermez one, read with `iden3::read_ptau`.
+//! The second is a `Ceremony` for one circuit: it derives initial keys from the powers and then takes
+//! any number of contributions, each re-randomizing `delta` and proving knowledge of its share.
+//!
+//! The final keys are sound if at least one participant of each phase threw their share away. That
+//! rests on the transcript both phases start from; keys from `groth16::setup` or
+//! `PowersOfTau::generate` have a single author who knows the trapdoor, and no amount of
+//! contributions on top changes that.
+
+use ark_bn254::{Bn254, Fr, G1Affine, G1Projective, G2Affine, G2Projective};
+use ark_ec::pairing::Pairing;
+use ark_ec::{AffineRepr, CurveGroup, VariableBaseMSM};
+use ark_ff::{Field, UniformRand, Zero};
+use ark_poly::{EvaluationDomain, Radix2EvaluationDomain};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::rand::{CryptoRng, Rng, RngCore};
+use rand::rngs::OsRng;
+use rand_chacha::ChaCha20Rng;
+use rand_chacha::rand_core::SeedableRng;
+use sha2::{Digest, Sha256};
+use crate::error::{Result, ZkError};
+use crate::field::Bn254Fr;
+use crate::format::{self, Header};
+use crate::groth16::{self, g1_to_be_bytes, ProvingKey, VerifyingKey};
+use crate::r1cs::R1CS;
+
+pub const PROVING_KEY_MAGIC: [u8; 4] = *b"ZKPK";
+pub const VERIFYING_KEY_MAGIC: [u8; 4] = *b"ZKVK";
+pub const CEREMONY_MAGIC: [u8; 4] = *b"ZKCR";
+pub const POWERS_OF_TAU_MAGIC: [u8; 4] = *b"ZKPT";
+
+/// Output of the circuit-independent phase of a setup: `tau^i`, `alpha * tau^i` and `beta * tau^i` in
+/// the groups Groth16 needs them in. It supports every circuit whose domain has at most `size()`
+/// points.
+#[derive(Clone, CanonicalSerialize, CanonicalDeserialize)]
+pub struct PowersOfTau {
+    /// `tau^i * G1` for `i < 2 * size - 1`.
+    pub tau_g1: Vec<G1Affine>,
+    /// `tau^i * G2` for `i < size`.
+    pub tau_g2: Vec<G2Affine>,
+    pub alpha_tau_g1: Vec<G1Affine>,
+    pub beta_tau_g1: Vec<G1Affine>,
+    pub beta_g2: G2Affine,
+}
+
+impl PowersOfTau {
+    /// Single-party powers for domains of up to `size` points. Whoever runs this knows `tau`, so it is
+    /// only fit for tests; production keys should start from a public ceremony.
+    pub fn generate<R: RngCore + CryptoRng>(size: usize, rng: &mut R) -> Result<Self> {
+        if !size.is_power_of_two() {
+            return Err(ZkError::InvalidParameters(format!("{size} is not a power of two")));
+        }
+        let (tau, alpha, beta) = (nonzero_scalar(rng), nonzero_scalar(rng), nonzero_scalar(rng));
+        let powers: Vec<Fr> = std::iter::successors(Some(Fr::from(1u64)), |power| Some(*power * tau))
+            .take(2 * size - 1)
+            .collect();
+        let g1 = |scalars: &mut dyn Iterator<Item = Fr>| {
+            G1Projective::normalize_batch(&scalars.map(|scalar| G1Affine::generator() * scalar).collect::<Vec<_>>())
+        };
+
+        Ok(PowersOfTau {
+            tau_g1: g1(&mut powers.iter().copied()),
+            tau_g2: G2Projective::normalize_batch(
+                &powers[..size].iter().map(|power| G2Affine::generator() * power).collect::<Vec<_>>(),
+            ),
+            alpha_tau_g1: g1(&mut powers[..size].iter().map(|power| alpha * power)),
+            beta_tau_g1: g1(&mut powers[..size].iter().map(|power| beta * power)),
+            beta_g2: (G2Affine::generator() * beta).into_affine(),
+        })
+    }
+
+    pub fn size(&self) -> usize {
+        self.tau_g2.len()
+    }
+
+    /// Check that the vectors hold consecutive powers of one non-zero `tau`, scaled by one non-zero
+    /// `alpha` and `beta`. This is all a consumer can check; it says nothing about who knows `tau`.
+    pub fn verify(&self) -> Result<()> {
+        let size = self.size();
+        let well_formed = size.is_power_of_two()
+            && self.tau_g1.len() == 2 * size - 1
+            && self.alpha_tau_g1.len() == size
+            && self.beta_tau_g1.len() == size
+            && self.tau_g1[0] == G1Affine::generator()
+            && self.tau_g2[0] == G2Affine::generator()
+            && !self.tau_g1.iter().chain(&self.alpha_tau_g1).chain(&self.beta_tau_g1).any(|point| point.is_zero());
+        if !well_formed {
+            return Err(ZkError::InvalidParameters("malformed powers of tau".to_string()));
+        }
+
+        let (g1, g2) = (G1Affine::generator(), G2Affine::generator());
+        let tau = if size > 1 { (self.tau_g1[1], self.tau_g2[1]) } else { (g1, g2) };
+        let consistent = same_ratio(successive(&self.tau_g1), (g2, tau.1))
+            && same_ratio((g1, tau.0), successive_g2(&self.tau_g2))
+            && same_ratio(successive(&self.alpha_tau_g1), (g2, tau.1))
+            && same_ratio(successive(&self.beta_tau_g1), (g2, tau.1))
+            && same_ratio((g1, self.beta_tau_g1[0]), (g2, self.beta_g2));
+        if !consistent {
+            return Err(ZkError::InvalidParameters("inconsistent powers of tau".to_string()));
+        }
+        Ok(())
+    }
+
+    pub fn save(&self, filename: &str) -> Result<()> {
+        write_key_file(filename, POWERS_OF_TAU_MAGIC, 0, self)
+    }
+
+    pub fn load(filename: &str) -> Result<Self> {
+        read_key_file(filename, POWERS_OF_TAU_MAGIC, |_| Ok(0))
+    }
+}
+
+/// Initial proving key for `r1cs` from the output of a Powers of Tau ceremony. `gamma` and `delta`
+/// are both one here, so the key must not be used before at least one `Ceremony` contribution.
+///
+/// The key uses the same QAP reduction as `groth16::setup`, over a domain with room for every
+/// constraint plus one per public input, so `groth16::prove` works with it unchanged.
+fn initial_key(r1cs: &R1CS, powers: &PowersOfTau) -> Result<ProvingKey> {
+    let matrices = groth16::constraint_matrices(r1cs)?;
+    let num_inputs = matrices.num_instance_variables;
+    let num_variables = num_inputs + matrices.num_witness_variables;
+    let required = matrices.num_constraints + num_inputs;
+    let domain = Radix2EvaluationDomain::<Fr>::new(required)
+        .filter(|domain| domain.size() <= powers.size())
+        .ok_or(ZkError::DomainSize { required, available: powers.size() })?;
+    let n = domain.size();
+
+    // The inverse FFT of `[tau^i]` is `[L_j(tau)]`, the Lagrange basis of the domain at `tau`.
+    let lagrange_g1 = |points: &[G1Affine]| {
+        domain.ifft(&points[..n].iter().map(|point| point.into_group()).collect::<Vec<_>>())
+    };
+    let lagrange = lagrange_g1(&powers.tau_g1);
+    let alpha_lagrange = lagrange_g1(&powers.alpha_tau_g1);
+    let beta_lagrange = lagrange_g1(&powers.beta_tau_g1);
+    let lagrange_g2 = domain.ifft(&powers.tau_g2[..n].iter().map(|point| point.into_group()).collect::<Vec<_>>());
+
+    let mut a = vec![G1Projective::zero(); num_variables];
+    let mut b_g1 = vec![G1Projective::zero(); num_variables];
+    let mut b_g2 = vec![G2Projective::zero(); num_variables];
+    // `beta * A_i(tau) + alpha * B_i(tau) + C_i(tau)`.
+    let mut abc = vec![G1Projective::zero(); num_variables];
+
+    // Like arkworks, pin every public input to one extra domain point so their A polynomials are
+    // linearly independent.
+    for input in 0..num_inputs {
+        a[input] = lagrange[matrices.num_constraints + input];
+        abc[input] = beta_lagrange[matrices.num_constraints + input];
+    }
+    for constraint in 0..matrices.num_constraints {
+        for (coeff, index) in &matrices.a[constraint] {
+            a[*index] += lagrange[constraint] * coeff;
+            abc[*index] += beta_lagrange[constraint] * coeff;
+        }
+        for (coeff, index) in &matrices.b[constraint] {
+            b_g1[*index] += lagrange[constraint] * coeff;
+            b_g2[*index] += lagrange_g2[constraint] * coeff;
+            abc[*index] += alpha_lagrange[constraint] * coeff;
+        }
+        for (coeff, index) in &matrices.c[constraint] {
+            abc[*index] += lagrange[constraint] * coeff;
+        }
+    }
+
+    // `tau^i * Z(tau) = tau^(i + n) - tau^i`.
+    let h_query: Vec<G1Projective> = (0..n - 1).map(|i| powers.tau_g1[i + n] - powers.tau_g1[i]).collect();
+    let abc = G1Projective::normalize_batch(&abc);
+
+    Ok(ProvingKey {
+        vk: VerifyingKey {
+            alpha_g1: powers.alpha_tau_g1[0],
+            beta_g2: powers.beta_g2,
+            gamma_g2: G2Affine::generator(),
+            delta_g2: G2Affine::generator(),
+            gamma_abc_g1: abc[..num_inputs].to_vec(),
+        },
+        beta_g1: powers.beta_tau_g1[0],
+        delta_g1: G1Affine::generator(),
+        a_query: G1Projective::normalize_batch(&a),
+        b_g1_query: G1Projective::normalize_batch(&b_g1),
+        b_g2_query: G2Projective::normalize_batch(&b_g2),
+        h_query: G1Projective::normalize_batch(&h_query),
+        l_query: abc[num_inputs..].to_vec(),
+    })
+}
+
+/// One participant's share of `delta`, with a proof that they knew it. `s_delta` and `r_delta` are
+/// `s` and `r` multiplied by the share, where `r` is hashed from the transcript so the proof cannot
+/// be lifted from an earlier contribution.
+#[derive(Clone, CanonicalSerialize, CanonicalDeserialize)]
+pub struct Contribution {
+    pub delta_after: G1Affine,
+    pub s: G1Affine,
+    pub s_delta: G1Affine,
+    pub r_delta: G2Affine,
+}
+
+/// Groth16 parameters in the middle of a multi-party setup. The keys are only usable once `verify`
+/// accepts them, which takes at least one contribution.
+#[derive(Clone, CanonicalSerialize, CanonicalDeserialize)]
+pub struct Ceremony {
+    proving_key: ProvingKey,
+    contributions: Vec<Contribution>,
+    /// Running hash over the initial key and every contribution so far.
+    transcript: [u8; 32],
+}
+
+impl Ceremony {
+    /// Start a ceremony for `r1cs` from the output of a Powers of Tau ceremony, which must pass
+    /// `PowersOfTau::verify`. Fails with `ZkError::DomainSize` if the powers are too few for the
+    /// circuit.
+    pub fn from_powers_of_tau(r1cs: &R1CS, powers: &PowersOfTau) -> Result<Self> {
+        powers.verify()?;
+        let initial = initial_key(r1cs, powers)?;
+        let transcript = Sha256::digest(groth16::to_bytes(&initial)?).into();
+        Ok(Ceremony { proving_key: initial, contributions: Vec::new(), transcript })
+    }
+
+    /// Multiply `delta` by a fresh secret share drawn from `rng`. The share is dropped before this
+    /// returns. The result is the contribution's transcript hash, which the participant publishes so
+    /// they can later find it in the output of `verify`.
+    pub fn contribute<R: RngCore + CryptoRng>(&mut self, rng: &mut R) -> Result<[u8; 32]> {
+        let share = nonzero_scalar(rng);
+        let share_inv = share.inverse().ok_or(ZkError::NoInverse)?;
+
+        let s = G1Projective::rand(rng).into_affine();
+        let s_delta = (s * share).into_affine();
+        let r = hash_to_g2(&self.transcript, &s, &s_delta);
+
+        let pk = &mut self.proving_key;
+        pk.delta_g1 = (pk.delta_g1 * share).into_affine();
+        pk.vk.delta_g2 = (pk.vk.delta_g2 * share).into_affine();
+        let scale = |query: &[G1Affine]| {
+            G1Projective::normalize_batch(&query.iter().map(|point| *point * share_inv).collect::<Vec<_>>())
+        };
+        pk.h_query = scale(&pk.h_query);
+        pk.l_query = scale(&pk.l_query);
+
+        let contribution = Contribution { delta_after: pk.delta_g1, s, s_delta, r_delta: (r * share).into_affine() };
+        self.transcript = next_transcript(&self.transcript, &contribution)?;
+        self.contributions.push(contribution);
+        Ok(self.transcript)
+    }
+
+    /// Check that the current keys are those `from_powers_of_tau` derives for `r1cs` from `powers`,
+    /// with every recorded contribution applied, and that each contributor knew their share. Returns
+    /// the transcript hash of every contribution, in order. A ceremony without contributions fails:
+    /// its `delta` is public.
+    pub fn verify(&self, r1cs: &R1CS, powers: &PowersOfTau) -> Result<Vec<[u8; 32]>> {
+        if self.contributions.is_empty() {
+            return Err(ZkError::InvalidParameters("the ceremony has no contributions".to_string()));
+        }
+        powers.verify()?;
+        let initial = &initial_key(r1cs, powers)?;
+        let pk = &self.proving_key;
+        let unchanged = pk.vk.alpha_g1 == initial.vk.alpha_g1
+            && pk.vk.beta_g2 == initial.vk.beta_g2
+            && pk.vk.gamma_g2 == initial.vk.gamma_g2
+            && pk.vk.gamma_abc_g1 == initial.vk.gamma_abc_g1
+            && pk.beta_g1 == initial.beta_g1
+            && pk.a_query == initial.a_query
+            && pk.b_g1_query == initial.b_g1_query
+            && pk.b_g2_query == initial.b_g2_query
+            && pk.h_query.len() == initial.h_query.len()
+            && pk.l_query.len() == initial.l_query.len();
+        if !unchanged {
+            return Err(ZkError::InvalidParameters("the keys do not extend the initial parameters".to_string()));
+        }
+
+        let mut transcript: [u8; 32] = Sha256::digest(groth16::to_bytes(initial)?).into();
+        let mut delta = initial.delta_g1;
+        let mut hashes = Vec::with_capacity(self.contributions.len());
+        for (index, contribution) in self.contributions.iter().enumerate() {
+            let r = hash_to_g2(&transcript, &contribution.s, &contribution.s_delta);
+            let valid = !contribution.s.is_zero()
+                && same_ratio((contribution.s, contribution.s_delta), (r, contribution.r_delta))
+                && same_ratio((delta, contribution.delta_after), (r, contribution.r_delta));
+            if !valid {
+                return Err(ZkError::InvalidContribution(index));
+            }
+            delta = contribution.delta_after;
+            transcript = next_transcript(&transcript, contribution)?;
+            hashes.push(transcript);
+        }
+
+        // `delta` in both groups and the `1 / delta` in H and L must all follow the last contribution.
+        let consistent = pk.delta_g1 == delta
+            && transcript == self.transcript
+            && same_ratio((initial.delta_g1, pk.delta_g1), (initial.vk.delta_g2, pk.vk.delta_g2))
+            && same_ratio(merge(&pk.h_query, &initial.h_query), (initial.vk.delta_g2, pk.vk.delta_g2))
+            && same_ratio(merge(&pk.l_query, &initial.l_query), (initial.vk.delta_g2, pk.vk.delta_g2));
+        if !consistent {
+            return Err(ZkError::InvalidParameters("the keys do not match the last contribution".to_string()));
+        }
+        Ok(hashes)
+    }
+
+    pub fn proving_key(&self) -> &ProvingKey {
+        &self.proving_key
+    }
+
+    pub fn verifying_key(&self) -> &VerifyingKey {
+        &self.proving_key.vk
+    }
+
+    pub fn contributions(&self) -> &[Contribution] {
+        &self.contributions
+    }
+
+    pub fn save(&self, filename: &str) -> Result<()> {
+        write_key_file(filename, CEREMONY_MAGIC, self.proving_key.a_query.len(), self)
+    }
+
+    pub fn load(filename: &str) -> Result<Self> {
+        read_key_file(filename, CEREMONY_MAGIC, |ceremony: &Ceremony| Ok(ceremony.proving_key.a_query.len()))
+    }
+}
+
+pub fn save_proving_key(pk: &ProvingKey, filename: &str) -> Result<()> {
+    write_key_file(filename, PROVING_KEY_MAGIC, pk.a_query.len(), pk)
+}
+
+pub fn load_proving_key(filename: &str) -> Result<ProvingKey> {
+    read_key_file(filename, PROVING_KEY_MAGIC, |pk: &ProvingKey| Ok(pk.a_query.len()))
+}
+
+pub fn save_verifying_key(vk: &VerifyingKey, filename: &str) -> Result<()> {
+    write_key_file(filename, VERIFYING_KEY_MAGIC, num_public_inputs(vk)?, vk)
+}
+
+pub fn load_verifying_key(filename: &str) -> Result<VerifyingKey> {
+    read_key_file(filename, VERIFYING_KEY_MAGIC, num_public_inputs)
+}
+
+/// A verifying key has one `gamma_abc_g1` point for the constant one and one per public input.
+fn num_public_inputs(vk: &VerifyingKey) -> Result<usize> {
+    vk.gamma_abc_g1
+        .len()
+        .checked_sub(1)
+        .ok_or_else(|| ZkError::Deserialization("verifying key has no point for the constant one".into()))
+}
+
+/// Key material is over BN254 and has no constraints of its own; the variable count is that of the
+/// circuit, or its public inputs for a verifying key.
+fn write_key_file<T: CanonicalSerialize>(filename: &str, magic: [u8; 4], num_variables: usize, value: &T) -> Result<()> {
+    format::write_file(filename, Header::new::<Bn254Fr>(magic, 0, num_variables), &groth16::to_bytes(value)?)
+}
+
+fn read_key_file<T: CanonicalDeserialize>(filename: &str, magic: [u8; 4], num_variables: impl Fn(&T) -> Result<usize>) -> Result<T> {
+    let (header, body) = format::read_file(filename, magic)?;
+    header.check_field::<Bn254Fr>()?;
+    let value = groth16::from_bytes(&body)?;
+    header.check_counts(0, num_variables(&value)?)?;
+    Ok(value)
+}
+
+/// Rust source for a `carbon_credit::verifier::Groth16VerifyingKey` constant named `name`, laid out
+/// for the program's alt_bn128 verifier. The on-chain `eligibility_dev_vk.rs` is this output under a
+/// short header, and a ceremony's key is embedded the same way.
+pub fn export_verifying_key(vk: &VerifyingKey, name: &str) -> String {
+    let solana = groth16::verifying_key_to_solana(vk);
+    let mut out = String::from("use crate::verifier::Groth16VerifyingKey;\n\n");
+    out += &format!("pub const {name}: Groth16VerifyingKey = Groth16VerifyingKey {{\n");
+    out += &format!("    alpha_g1: {},\n", byte_array(&solana.alpha_g1, "    "));
+    out += &format!("    beta_g2: {},\n", byte_array(&solana.beta_g2, "    "));
+    out += &format!("    gamma_g2: {},\n", byte_array(&solana.gamma_g2, "    "));
+    out += &format!("    delta_g2: {},\n", byte_array(&solana.delta_g2, "    "));
+    out += "    ic: &[\n";
+    for point in &solana.ic {
+        out += &format!("        {},\n", byte_array(point, "        "));
+    }
+    out += "    ],\n};\n";
+    out
+}
+
+fn byte_array(bytes: &[u8], indent: &str) -> String {
+    let mut out = String::from("[\n");
+    for chunk in bytes.chunks(16) {
+        let line: Vec<String> = chunk.iter().map(|byte| format!("0x{byte:02x}")).collect();
+        out += &format!("{indent}    {},\n", line.join(", "));
+    }
+    out + indent + "]"
+}
+
+fn nonzero_scalar<R: RngCore>(rng: &mut R) -> Fr {
+    loop {
+        let scalar = Fr::rand(rng);
+        if !scalar.is_zero() {
+            return scalar;
+        }
+    }
+}
+
+/// `e(a.0, b.1) == e(a.1, b.0)`: the G1 pair and the G2 pair differ by the same factor.
+fn same_ratio(a: (G1Affine, G1Affine), b: (G2Affine, G2Affine)) -> bool {
+    Bn254::pairing(a.0, b.1) == Bn254::pairing(a.1, b.0)
+}
+
+/// A random linear combination of `left` and the same combination of `right`. The pair has the same
+/// ratio as every `(left[i], right[i])` if they all share one, and otherwise almost surely does not.
+fn merge(left: &[G1Affine], right: &[G1Affine]) -> (G1Affine, G1Affine) {
+    let scalars: Vec<Fr> = (0..left.len()).map(|_| Fr::rand(&mut OsRng)).collect();
+    (
+        G1Projective::msm_unchecked(left, &scalars).into_affine(),
+        G1Projective::msm_unchecked(right, &scalars).into_affine(),
+    )
+}
+
+/// `merge` of each point with its successor.
+fn successive(points: &[G1Affine]) -> (G1Affine, G1Affine) {
+    merge(&points[..points.len() - 1], &points[1..])
+}
+
+fn successive_g2(points: &[G2Affine]) -> (G2Affine, G2Affine) {
+    let scalars: Vec<Fr> = (1..points.len()).map(|_| Fr::rand(&mut OsRng)).collect();
+    (
+        G2Projective::msm_unchecked(&points[..points.len() - 1], &scalars).into_affine(),
+        G2Projective::msm_unchecked(&points[1..], &scalars).into_affine(),
+    )
+}
+
+fn next_transcript(transcript: &[u8; 32], contribution: &Contribution) -> Result<[u8; 32]> {
+    Ok(Sha256::new().chain_update(transcript).chain_update(groth16::to_bytes(contribution)?).finalize().into())
+}
+
+/// A G2 point nobody knows the discrete log of, derived from the transcript and the contributor's
+/// `s` pair: try-and-increment on random x coordinates, then clear the cofactor.
+fn hash_to_g2(transcript: &[u8; 32], s: &G1Affine, s_delta: &G1Affine) -> G2Affine {
+    let seed = Sha256::new()
+        .chain_update(transcript)
+        .chain_update(g1_to_be_bytes(s))
+        .chain_update(g1_to_be_bytes(s_delta))
+        .finalize();
+    let mut rng = ChaCha20Rng::from_seed(seed.into());
+    loop {
+        let x = UniformRand::rand(&mut rng);
+        if let Some(point) = G2Affine::get_point_from_x_unchecked(x, rng.r#gen()) {
+            let point = point.clear_cofactor();
+            if !point.is_zero() {
+                return point;
+            }
+        }
+    }
+}
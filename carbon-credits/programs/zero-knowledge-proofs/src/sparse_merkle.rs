@@ -0,0 +1,237 @@
+//! Sparse Merkle tree over Poseidon, keyed by 32-byte keys such as a `Pubkey` or a claim ID. Only
+//! non-empty nodes are stored, so updates touch `DEPTH` nodes however many leaves there are, and the
+//! whole state serializes compactly while the program only needs to keep the root.
+//!
+//! A key lives in the slot given by all the bits of its hash, so no two keys share a slot. The leaf
+//! commits to both the key and the value, and a non-membership proof shows an empty slot.
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufReader, Write};
+use num_bigint::BigUint;
+use num_traits::{One, Zero};
+use serde::{Deserialize, Serialize};
+use crate::error::{Result, ZkError};
+use crate::field::FieldElement;
+use crate::poseidon;
+
+pub type Key = [u8; 32];
+
+/// Levels below the root: one per bit of a key hash, which is a 254-bit BN254 scalar.
+pub const DEPTH: usize = 254;
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct SparseMerkleTree {
+    /// Occupied slots: the key and value stored there.
+    leaves: HashMap<BigUint, (Key, FieldElement)>,
+    /// Nodes that differ from the empty subtree of their level, keyed by `(level, index)` with level 0
+    /// for the leaves and `DEPTH` for the root.
+    nodes: HashMap<(usize, BigUint), FieldElement>,
+    /// Root of an empty subtree at each level.
+    empty: Vec<FieldElement>,
+}
+
+/// Siblings from the leaf up to the root, plus the key and value in the key's slot if it is taken.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SparseMerkleProof {
+    pub occupant: Option<(Key, FieldElement)>,
+    pub siblings: Vec<FieldElement>,
+}
+
+impl Default for SparseMerkleTree {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SparseMerkleTree {
+    pub fn new() -> Self {
+        SparseMerkleTree { leaves: HashMap::new(), nodes: HashMap::new(), empty: empty_subtrees() }
+    }
+
+    pub fn depth(&self) -> usize {
+        DEPTH
+    }
+
+    pub fn root(&self) -> FieldElement {
+        self.node(DEPTH, &BigUint::zero())
+    }
+
+    /// The root as the 32-byte big-endian value to store on-chain.
+    pub fn root_bytes(&self) -> [u8; 32] {
+        self.root().to_bytes_be()
+    }
+
+    pub fn len(&self) -> usize {
+        self.leaves.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.leaves.is_empty()
+    }
+
+    pub fn get(&self, key: &Key) -> Option<FieldElement> {
+        match self.leaves.get(&slot(key)) {
+            Some((occupant, value)) if occupant == key => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub fn contains(&self, key: &Key) -> bool {
+        self.get(key).is_some()
+    }
+
+    pub fn insert(&mut self, key: Key, value: FieldElement) -> Result<()> {
+        let slot = slot(&key);
+        if self.leaves.contains_key(&slot) {
+            return Err(ZkError::KeyExists);
+        }
+        self.set_leaf(&slot, leaf_hash(&key, &value));
+        self.leaves.insert(slot, (key, value));
+        Ok(())
+    }
+
+    /// Replace the value of `key` and return the previous one.
+    pub fn update(&mut self, key: &Key, value: FieldElement) -> Result<FieldElement> {
+        let slot = slot(key);
+        let previous = self.get(key).ok_or(ZkError::KeyNotFound)?;
+        self.set_leaf(&slot, leaf_hash(key, &value));
+        self.leaves.insert(slot, (*key, value));
+        Ok(previous)
+    }
+
+    /// Remove `key` and return its value.
+    pub fn delete(&mut self, key: &Key) -> Result<FieldElement> {
+        let slot = slot(key);
+        let previous = self.get(key).ok_or(ZkError::KeyNotFound)?;
+        self.leaves.remove(&slot);
+        self.set_leaf(&slot, self.empty[0]);
+        Ok(previous)
+    }
+
+    /// Proof for `key`'s slot. It proves membership if the key is present and non-membership otherwise.
+    pub fn prove(&self, key: &Key) -> SparseMerkleProof {
+        let slot = slot(key);
+        let siblings = (0..DEPTH).map(|level| self.node(level, &sibling(&(&slot >> level)))).collect();
+        SparseMerkleProof { occupant: self.leaves.get(&slot).copied(), siblings }
+    }
+
+    pub fn save_to_binary(&self, filename: &str) -> Result<()> {
+        let mut file = File::create(filename)?;
+        let encoded: Vec<u8> = bincode::serialize(&self).map_err(|error| ZkError::Serialization(error.to_string()))?;
+        file.write_all(&encoded)?;
+        Ok(())
+    }
+
+    pub fn load_from_binary(filename: &str) -> Result<Self> {
+        let file = BufReader::new(File::open(filename)?);
+        let tree: SparseMerkleTree =
+            bincode::deserialize_from(file).map_err(|error| ZkError::Deserialization(error.to_string()))?;
+        if tree.empty != empty_subtrees() {
+            return Err(ZkError::Deserialization("empty subtrees do not match the tree depth".into()));
+        }
+        Ok(tree)
+    }
+
+    fn node(&self, level: usize, index: &BigUint) -> FieldElement {
+        self.nodes.get(&(level, index.clone())).copied().unwrap_or(self.empty[level])
+    }
+
+    /// Write a leaf and rehash its path to the root, dropping nodes that became empty again.
+    fn set_leaf(&mut self, slot: &BigUint, leaf: FieldElement) {
+        let mut index = slot.clone();
+        let mut current = leaf;
+        for level in 0..=DEPTH {
+            if current == self.empty[level] {
+                self.nodes.remove(&(level, index.clone()));
+            } else {
+                self.nodes.insert((level, index.clone()), current);
+            }
+            if level == DEPTH {
+                break;
+            }
+
+            let sibling = self.node(level, &sibling(&index));
+            current = if index.bit(0) {
+                poseidon::hash(&[sibling, current])
+            } else {
+                poseidon::hash(&[current, sibling])
+            };
+            index >>= 1;
+        }
+    }
+}
+
+impl SparseMerkleProof {
+    /// Check that `key` maps to `value` under `root`.
+    pub fn verify_membership(&self, root: &FieldElement, key: &Key, value: &FieldElement) -> bool {
+        if !self.has_valid_depth() {
+            return false;
+        }
+        match &self.occupant {
+            Some((occupant, stored)) if occupant == key && stored == value => {
+                self.root_for(key, leaf_hash(key, value)) == *root
+            },
+            _ => false,
+        }
+    }
+
+    /// Check that `key` is absent under `root`: its slot is empty.
+    pub fn verify_non_membership(&self, root: &FieldElement, key: &Key) -> bool {
+        self.has_valid_depth() && self.occupant.is_none() && self.root_for(key, FieldElement::zero()) == *root
+    }
+
+    fn has_valid_depth(&self) -> bool {
+        self.siblings.len() == DEPTH
+    }
+
+    fn root_for(&self, key: &Key, leaf: FieldElement) -> FieldElement {
+        let index = slot(key);
+        self.siblings.iter().enumerate().fold(leaf, |current, (level, sibling)| {
+            if index.bit(level as u64) {
+                poseidon::hash(&[*sibling, current])
+            } else {
+                poseidon::hash(&[current, *sibling])
+            }
+        })
+    }
+}
+
+/// A key as a field element: Poseidon of its two 128-bit halves, so every 32-byte key fits.
+pub fn key_hash(key: &Key) -> FieldElement {
+    poseidon::hash(&key_halves(key))
+}
+
+/// The big-endian 128-bit halves of a key, high half first.
+pub fn key_halves(key: &Key) -> [FieldElement; 2] {
+    let half = |bytes: &[u8]| {
+        let mut padded = [0u8; 32];
+        padded[16..].copy_from_slice(bytes);
+        FieldElement::from_bytes_be(&padded).expect("128-bit values are below the modulus")
+    };
+    [half(&key[..16]), half(&key[16..])]
+}
+
+/// Leaf commitment to a key and its value.
+pub fn leaf_hash(key: &Key, value: &FieldElement) -> FieldElement {
+    poseidon::hash(&[key_hash(key), *value])
+}
+
+/// The key hash as a `DEPTH`-bit leaf index.
+fn slot(key: &Key) -> BigUint {
+    BigUint::from_bytes_le(&key_hash(key).to_bytes_le())
+}
+
+/// Index of the other child of the same parent.
+fn sibling(index: &BigUint) -> BigUint {
+    index ^ BigUint::one()
+}
+
+/// Root of an empty subtree at each level, from the empty leaf up to an empty tree.
+fn empty_subtrees() -> Vec<FieldElement> {
+    let mut empty = vec![FieldElement::zero()];
+    for level in 0..DEPTH {
+        empty.push(poseidon::hash(&[empty[level], empty[level]]));
+    }
+    empty
+}
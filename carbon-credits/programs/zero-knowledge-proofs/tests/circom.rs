@@ -0,0 +1,39 @@
+use zero_knowledge_proofs::error::ZkError;
+use zero_knowledge_proofs::field::{Bn254Fr, FieldElement};
+use zero_knowledge_proofs::{iden3, proof};
+
+const R1CS_FILE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/circom/multiplier.r1cs");
+const WTNS_FILE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/circom/multiplier.wtns");
+
+#[test]
+fn circom_circuits_prove_with_the_crate_backend() {
+    let (header, _) = iden3::read_r1cs::<Bn254Fr>(R1CS_FILE).unwrap();
+    assert_eq!((header.num_wires, header.num_public_outputs, header.num_public_inputs), (4, 1, 1));
+
+    let r1cs = iden3::read_circuit::<Bn254Fr>(R1CS_FILE, WTNS_FILE).unwrap();
+    let artifacts = proof::prove_r1cs(r1cs).unwrap();
+    // The output `c` and the public input `a`, in circom's wire order.
+    assert_eq!(artifacts.public_inputs, vec![FieldElement::from_u64(33), FieldElement::from_u64(3)]);
+    assert!(proof::verify(&artifacts.verifying_key, &artifacts.public_inputs, &artifacts.proof));
+
+    let wrong = [FieldElement::from_u64(34), FieldElement::from_u64(3)];
+    assert!(!proof::verify(&artifacts.verifying_key, &wrong, &artifacts.proof));
+}
+
+#[test]
+fn witnesses_that_do_not_fit_the_circuit_are_rejected() {
+    let dir = std::env::temp_dir().join(format!("zkp-circom-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = |name: &str| dir.join(name).to_str().unwrap().to_string();
+
+    let values = |values: &[u64]| values.iter().map(|value| FieldElement::<Bn254Fr>::from_u64(*value)).collect::<Vec<_>>();
+    iden3::write_wtns(&values(&[1, 34, 3, 11]), &path("wrong.wtns")).unwrap();
+    assert!(matches!(iden3::read_circuit::<Bn254Fr>(R1CS_FILE, &path("wrong.wtns")), Err(ZkError::Unsatisfied)));
+    iden3::write_wtns(&values(&[1, 33, 3]), &path("short.wtns")).unwrap();
+    assert!(matches!(
+        iden3::read_circuit::<Bn254Fr>(R1CS_FILE, &path("short.wtns")),
+        Err(ZkError::LengthMismatch { expected: 4, actual: 3 })
+    ));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,13 @@
+pragma circom 2.1.6;
+
+// multiplier.r1cs and multiplier.wtns next to this file encode this circuit in the iden3 formats,
+// with the witness for a = 3, b = 11. Wires: 0 = one, 1 = c, 2 = a, 3 = b.
+template Multiplier() {
+    signal input a;
+    signal input b;
+    signal output c;
+
+    c <== a * b;
+}
+
+component main {public [a]} = Multiplier();
@@ -0,0 +1,57 @@
+use std::process::{Command, Output};
+
+fn zkp(dir: &std::path::Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_zkp")).current_dir(dir).args(args).output().unwrap()
+}
+
+#[test]
+fn setup_prove_and_verify_report_through_the_exit_code() {
+    let dir = std::env::temp_dir().join(format!("zkp-cli-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let inputs = r#"{"borrower": "0707070707070707070707070707070707070707070707070707070707070707",
+        "threshold": 3000, "total_credits_returned": 50, "carbon_credits": 40, "reputation": 90}"#;
+    std::fs::write(dir.join("inputs.json"), inputs).unwrap();
+
+    assert!(zkp(&dir, &["setup"]).status.success());
+    assert!(zkp(&dir, &["prove", "--inputs", "inputs.json"]).status.success());
+    let verify = ["verify", "--vk", "generated-proofs/verifying_key.bin", "--proof", "generated-proofs/proof.bin"];
+    assert_eq!(zkp(&dir, &[&verify[..], &["--public", "generated-proofs/public.json"]].concat()).status.code(), Some(0));
+
+    // The same proof against a higher threshold.
+    let public = std::fs::read_to_string(dir.join("generated-proofs/public.json")).unwrap();
+    std::fs::write(dir.join("higher.json"), public.replacen("\"3000\"", "\"3001\"", 1)).unwrap();
+    assert_eq!(zkp(&dir, &[&verify[..], &["--public", "higher.json"]].concat()).status.code(), Some(1));
+
+    let inspect = zkp(&dir, &["inspect", "generated-proofs/r1cs_file.bin"]);
+    assert!(String::from_utf8(inspect.stdout).unwrap().contains("constraint system (ZKR1)"));
+    let exported = zkp(&dir, &["export-verifier"]);
+    assert!(String::from_utf8(exported.stdout).unwrap().contains("pub const ELIGIBILITY_VERIFYING_KEY: Groth16VerifyingKey"));
+
+    assert_eq!(zkp(&dir, &["verify", "--vk", "missing.bin"]).status.code(), Some(2));
+    assert_eq!(zkp(&dir, &["frobnicate"]).status.code(), Some(2));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[cfg(feature = "dev-keys")]
+#[test]
+fn development_keys_export_the_embedded_development_key() {
+    let dir = std::env::temp_dir().join(format!("zkp-cli-dev-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+
+    assert!(zkp(&dir, &["setup", "--dev"]).status.success());
+    let exported = zkp(&dir, &["export-verifier", "--name", "ELIGIBILITY_DEV_VERIFYING_KEY"]);
+    let embedded = include_str!("../../carbon-credits/src/eligibility_dev_vk.rs");
+    assert!(embedded.ends_with(&String::from_utf8(exported.stdout).unwrap()));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[cfg(not(feature = "dev-keys"))]
+#[test]
+fn development_keys_need_the_feature() {
+    let dir = std::env::temp_dir().join(format!("zkp-cli-dev-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    assert_eq!(zkp(&dir, &["setup", "--dev"]).status.code(), Some(2));
+    std::fs::remove_dir_all(&dir).unwrap();
+}
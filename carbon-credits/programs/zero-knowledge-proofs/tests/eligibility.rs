@@ -0,0 +1,66 @@
+use zero_knowledge_proofs::error::ZkError;
+use zero_knowledge_proofs::field::FieldElement;
+use rand::rngs::OsRng;
+use zero_knowledge_proofs::groth16;
+use zero_knowledge_proofs::r1cs::R1CS;
+use zero_knowledge_proofs::{
+    borrower_commitment, eligibility_circuit, eligibility_proof, eligibility_public_inputs, record_commitment,
+    ELIGIBILITY_PUBLIC_INPUTS,
+};
+
+#[test]
+fn only_the_threshold_and_the_commitments_are_public() {
+    let borrower = [3u8; 32];
+    let r1cs = eligibility_circuit(50, 40, 90, 3_000, &borrower).unwrap().to_r1cs().unwrap();
+    let witness = r1cs.generate_witness();
+    assert!(r1cs.verify_witness(&witness));
+    assert_eq!(r1cs.num_public(), ELIGIBILITY_PUBLIC_INPUTS);
+    assert_eq!(
+        r1cs.public_inputs(&witness).unwrap(),
+        &[FieldElement::from_u64(3_000), borrower_commitment(&borrower), record_commitment(50, 40, 90)],
+    );
+    let balances = [50, 40, 90].map(FieldElement::from_u64);
+    assert!(balances.iter().all(|balance| !r1cs.public_inputs(&witness).unwrap().contains(balance)));
+    let revealed: Vec<[u8; 32]> = r1cs.public_inputs(&witness).unwrap().iter().map(FieldElement::to_bytes_be).collect();
+    assert_eq!(eligibility_public_inputs(3_000, &borrower, 50, 40, 90).to_vec(), revealed);
+
+    let mut r1cs: R1CS = R1CS::new();
+    r1cs.add_public_input(FieldElement::one()).unwrap();
+    r1cs.add_variable(FieldElement::one());
+    assert!(matches!(r1cs.add_public_input(FieldElement::one()), Err(ZkError::InvalidParameters(_))));
+}
+
+#[test]
+fn proofs_are_bound_to_the_threshold_the_borrower_and_their_record() {
+    let borrower = [3u8; 32];
+    // 90 * 40 + 50 = 3650.
+    let r1cs = eligibility_circuit(50, 40, 90, 3_650, &borrower).unwrap().to_r1cs().unwrap();
+    let (pk, vk) = groth16::setup(&r1cs, &mut OsRng).unwrap();
+    let proof = groth16::prove(&pk, &r1cs, &r1cs.generate_witness(), &mut OsRng).unwrap();
+    let inputs = |threshold: u64, borrower: &[u8; 32], reputation: u64| {
+        eligibility_public_inputs(threshold, borrower, 50, 40, reputation).map(|input| FieldElement::from_bytes_be(&input).unwrap())
+    };
+    assert!(groth16::verify(&vk, &inputs(3_650, &borrower, 90), &proof));
+    assert!(!groth16::verify(&vk, &inputs(3_651, &borrower, 90), &proof));
+    assert!(!groth16::verify(&vk, &inputs(3_650, &[4u8; 32], 90), &proof));
+    // The record on-chain, not the one the prover claims, decides the public inputs.
+    assert!(!groth16::verify(&vk, &inputs(3_650, &borrower, 89), &proof));
+
+    assert_eq!(eligibility_proof(&pk, &borrower, 3_650, 50, 40, 90).unwrap().len(), groth16::SOLANA_PROOF_LEN);
+    assert!(matches!(eligibility_proof(&pk, &borrower, 3_651, 50, 40, 90), Err(ZkError::Unsatisfied)));
+}
+
+#[test]
+fn full_width_balances_do_not_wrap_the_score() {
+    let borrower = [3u8; 32];
+    let satisfied = |total_credits_returned, carbon_credits, reputation, threshold| {
+        let r1cs = eligibility_circuit(total_credits_returned, carbon_credits, reputation, threshold, &borrower)
+            .unwrap()
+            .to_r1cs()
+            .unwrap();
+        r1cs.verify_witness(&r1cs.generate_witness())
+    };
+    assert!(satisfied(u64::MAX, u64::MAX, u64::MAX, u64::MAX));
+    assert!(satisfied(0, u64::MAX, 2, u64::MAX));
+    assert!(!satisfied(0, 0, 0, 1));
+}
@@ -0,0 +1,58 @@
+use rand::rngs::OsRng;
+use zero_knowledge_proofs::circuit::{Circuit, Gate};
+use zero_knowledge_proofs::error::ZkError;
+use zero_knowledge_proofs::field::{Bn254Fr, FieldElement, Goldilocks};
+use zero_knowledge_proofs::r1cs::R1CS;
+use zero_knowledge_proofs::{eligibility_circuit, groth16};
+
+#[test]
+fn zero_has_no_inverse() {
+    let zero = FieldElement::<Bn254Fr>::zero();
+    assert!(matches!(zero.inv(), Err(ZkError::NoInverse)));
+    assert!(matches!(FieldElement::<Bn254Fr>::one().div(&zero), Err(ZkError::NoInverse)));
+}
+
+#[test]
+fn gates_on_missing_wires_are_rejected() {
+    let mut r1cs: R1CS = R1CS::new();
+    let a = r1cs.add_variable(FieldElement::from_u64(2));
+    let one = FieldElement::one();
+    assert!(matches!(r1cs.add_constraint(&[(a, one)], &[(a + 1, one)], &[]), Err(ZkError::InvalidWire(index)) if index == a + 1));
+    assert!(r1cs.constraints.is_empty());
+
+    let mut cs: Circuit = Circuit::new();
+    let input = cs.add_input(FieldElement::from_u64(3));
+    cs.add_gate(Gate::Mul(input, input, 7));
+    assert!(matches!(cs.to_r1cs(), Err(ZkError::InvalidWire(7))));
+}
+
+#[test]
+fn bad_witnesses_and_encodings_are_errors() {
+    let r1cs = eligibility_circuit(3, 4, 5, 23, &[1; 32]).unwrap().to_r1cs().unwrap();
+    let (pk, _) = groth16::setup(&r1cs, &mut OsRng).unwrap();
+    let mut witness = r1cs.generate_witness();
+    witness[1] += FieldElement::one();
+    assert!(matches!(groth16::prove(&pk, &r1cs, &witness, &mut OsRng), Err(ZkError::Unsatisfied)));
+    assert!(matches!(groth16::prove(&pk, &r1cs, &witness[1..], &mut OsRng), Err(ZkError::LengthMismatch { .. })));
+
+    let proof = groth16::prove(&pk, &r1cs, &r1cs.generate_witness(), &mut OsRng).unwrap();
+    let bytes = groth16::to_bytes(&proof).unwrap();
+    assert!(matches!(groth16::from_bytes::<groth16::Groth16Proof>(&bytes[1..]), Err(ZkError::Deserialization(_))));
+}
+
+#[test]
+fn r1cs_files_remember_their_field() {
+    let path = std::env::temp_dir().join(format!("r1cs-field-{}.bin", std::process::id()));
+    let path = path.to_str().unwrap();
+    let r1cs = eligibility_circuit(3, 4, 5, 23, &[1; 32]).unwrap().to_r1cs().unwrap();
+    r1cs.save_to_binary(path).unwrap();
+
+    let loaded: R1CS = R1CS::load_from_binary(path).unwrap();
+    assert!(loaded.verify_witness(&r1cs.generate_witness()));
+    assert!(matches!(
+        R1CS::<Goldilocks>::load_from_binary(path),
+        Err(ZkError::ModulusMismatch { expected, found }) if expected == "goldilocks" && found == "bn254-fr",
+    ));
+    std::fs::remove_file(path).unwrap();
+    assert!(matches!(R1CS::<Bn254Fr>::load_from_binary(path), Err(ZkError::Io(_))));
+}
@@ -0,0 +1,186 @@
+use num_bigint::{BigInt, BigUint, Sign};
+use num_integer::Integer;
+use proptest::prelude::*;
+use zero_knowledge_proofs::field::{batch_inverse, Bls12_381Fr, Bn254Fr, FieldElement, FieldParams, Goldilocks};
+
+/// Arbitrary integers of either sign, up to 320 bits, so reduction from well above the modulus and
+/// from negative values is exercised.
+fn integer() -> impl Strategy<Value = BigInt> {
+    (any::<[u32; 10]>(), any::<bool>()).prop_map(|(digits, negative)| {
+        let sign = if negative { Sign::Minus } else { Sign::Plus };
+        BigInt::from_biguint(sign, BigUint::new(digits.to_vec()))
+    })
+}
+
+fn element<F: FieldParams>() -> impl Strategy<Value = FieldElement<F>> {
+    prop_oneof![
+        1 => Just(FieldElement::zero()),
+        1 => Just(FieldElement::one()),
+        1 => Just(-FieldElement::one()),
+        12 => integer().prop_map(FieldElement::new),
+    ]
+}
+
+macro_rules! field_properties {
+    ($module:ident, $field:ty) => {
+        mod $module {
+            use super::*;
+
+            type Fe = FieldElement<$field>;
+
+            fn modulus() -> BigInt {
+                Fe::modulus()
+            }
+
+            proptest! {
+                #[test]
+                fn new_reduces_to_the_canonical_representative(value in integer()) {
+                    let element = Fe::new(value.clone());
+                    prop_assert_eq!(element.get_value(), value.mod_floor(&modulus()));
+                    prop_assert_eq!(element, Fe::new(value + modulus()));
+                }
+
+                #[test]
+                fn arithmetic_matches_integers_mod_p(a in element::<$field>(), b in element::<$field>()) {
+                    let (x, y, p) = (a.get_value(), b.get_value(), modulus());
+                    prop_assert_eq!((a + b).get_value(), (&x + &y).mod_floor(&p));
+                    prop_assert_eq!((a - b).get_value(), (&x - &y).mod_floor(&p));
+                    prop_assert_eq!((a * b).get_value(), (&x * &y).mod_floor(&p));
+                    prop_assert_eq!((-a).get_value(), (-&x).mod_floor(&p));
+                }
+
+                #[test]
+                fn addition_is_an_abelian_group(
+                    a in element::<$field>(),
+                    b in element::<$field>(),
+                    c in element::<$field>(),
+                ) {
+                    prop_assert_eq!(a + b, b + a);
+                    prop_assert_eq!((a + b) + c, a + (b + c));
+                    prop_assert_eq!(a + Fe::zero(), a);
+                    prop_assert_eq!(a + (-a), Fe::zero());
+                    prop_assert_eq!(a - b, a + (-b));
+                }
+
+                #[test]
+                fn multiplication_is_a_commutative_monoid(
+                    a in element::<$field>(),
+                    b in element::<$field>(),
+                    c in element::<$field>(),
+                ) {
+                    prop_assert_eq!(a * b, b * a);
+                    prop_assert_eq!((a * b) * c, a * (b * c));
+                    prop_assert_eq!(a * Fe::one(), a);
+                    prop_assert_eq!(a * Fe::zero(), Fe::zero());
+                }
+
+                #[test]
+                fn multiplication_distributes_over_addition(
+                    a in element::<$field>(),
+                    b in element::<$field>(),
+                    c in element::<$field>(),
+                ) {
+                    prop_assert_eq!(a * (b + c), a * b + a * c);
+                }
+
+                #[test]
+                fn non_zero_elements_are_invertible(a in element::<$field>(), b in element::<$field>()) {
+                    prop_assume!(!a.is_zero());
+                    prop_assert_eq!(a * a.inv().unwrap(), Fe::one());
+                    prop_assert_eq!((b / a) * a, b);
+                }
+
+                #[test]
+                fn reference_and_assigning_operators_agree(a in element::<$field>(), b in element::<$field>()) {
+                    prop_assert_eq!(&a + &b, a + b);
+                    prop_assert_eq!(&a - b, a - &b);
+                    prop_assert_eq!(&a * &b, a * b);
+                    prop_assert_eq!(-&a, -a);
+
+                    let mut c = a;
+                    c += b;
+                    c -= &b;
+                    c *= b;
+                    prop_assert_eq!(c, a * b);
+                    if !b.is_zero() {
+                        c /= &b;
+                        prop_assert_eq!(c, a);
+                    }
+                }
+
+                #[test]
+                fn pow_matches_modular_exponentiation(a in element::<$field>(), exponent in any::<u64>()) {
+                    let expected = a.get_value().modpow(&BigInt::from(exponent), &modulus());
+                    prop_assert_eq!(a.pow(exponent).get_value(), expected);
+                    prop_assert_eq!(a.pow(0), Fe::one());
+                }
+
+                #[test]
+                fn squares_have_square_roots(a in element::<$field>()) {
+                    let square = a.square();
+                    let root = square.sqrt().expect("a square has a root");
+                    prop_assert_eq!(root.square(), square);
+                    prop_assert!(root == a || root == -a);
+                    prop_assert_eq!(square.legendre(), if a.is_zero() { 0 } else { 1 });
+                }
+
+                #[test]
+                fn non_squares_have_no_square_root(a in element::<$field>()) {
+                    prop_assert_eq!(a.sqrt().is_some(), a.legendre() != -1);
+                }
+
+                #[test]
+                fn batch_inverse_matches_single_inversions(values in prop::collection::vec(element::<$field>(), 0..16)) {
+                    let inverses = batch_inverse(&values);
+                    prop_assert_eq!(inverses.len(), values.len());
+                    for (value, inverse) in values.iter().zip(&inverses) {
+                        if value.is_zero() {
+                            prop_assert!(inverse.is_zero());
+                        } else {
+                            prop_assert_eq!(*inverse, value.inv().unwrap());
+                        }
+                    }
+                }
+
+                #[test]
+                fn bytes_round_trip(a in element::<$field>()) {
+                    prop_assert_eq!(Fe::from_bytes_be(&a.to_bytes_be()), Some(a));
+                    prop_assert_eq!(Fe::from_bytes_le(&a.to_bytes_le()), Some(a));
+
+                    let mut be = a.to_bytes_be();
+                    be.reverse();
+                    prop_assert_eq!(be, a.to_bytes_le());
+                    prop_assert_eq!(BigInt::from_bytes_be(Sign::Plus, &a.to_bytes_be()), a.get_value());
+                }
+
+                #[test]
+                fn serde_round_trip(a in element::<$field>()) {
+                    let bytes = bincode::serialize(&a).unwrap();
+                    prop_assert_eq!(bincode::deserialize::<Fe>(&bytes).unwrap(), a);
+                }
+            }
+
+            #[test]
+            fn non_canonical_bytes_are_rejected() {
+                let (_, modulus_le) = modulus().to_bytes_le();
+                let mut bytes = [0u8; 32];
+                bytes[..modulus_le.len()].copy_from_slice(&modulus_le);
+                assert_eq!(Fe::from_bytes_le(&bytes), None);
+                assert_eq!(Fe::from_bytes_le(&[0xff; 32]), None);
+            }
+
+            #[test]
+            fn batch_inverse_skips_zeroes() {
+                let values = [Fe::from_u64(3), Fe::zero(), Fe::from_u64(7)];
+                let inverses = batch_inverse(&values);
+                assert_eq!(inverses[0] * values[0], Fe::one());
+                assert!(inverses[1].is_zero());
+                assert_eq!(inverses[2] * values[2], Fe::one());
+            }
+        }
+    };
+}
+
+field_properties!(bn254, Bn254Fr);
+field_properties!(bls12_381, Bls12_381Fr);
+field_properties!(goldilocks, Goldilocks);
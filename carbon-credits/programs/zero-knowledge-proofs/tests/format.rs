@@ -0,0 +1,83 @@
+use zero_knowledge_proofs::error::ZkError;
+use zero_knowledge_proofs::field::{Bn254Fr, FieldElement, Goldilocks};
+use zero_knowledge_proofs::format::{self, Header, HEADER_LEN};
+use zero_knowledge_proofs::proof::Proof;
+use zero_knowledge_proofs::r1cs::R1CS;
+use zero_knowledge_proofs::{eligibility_circuit, iden3};
+
+fn temp_dir(name: &str) -> std::path::PathBuf {
+    let dir = std::env::temp_dir().join(format!("zkp-{name}-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[test]
+fn headers_describe_the_file_and_guard_its_body() {
+    let dir = temp_dir("format");
+    let path = |name: &str| dir.join(name).to_str().unwrap().to_string();
+    let r1cs = eligibility_circuit(3, 4, 5, 23, &[1; 32]).unwrap().to_r1cs().unwrap();
+    r1cs.save_to_binary(&path("r1cs.bin")).unwrap();
+
+    let header = format::read_header(&path("r1cs.bin")).unwrap();
+    assert_eq!(header.magic, format::R1CS_MAGIC);
+    assert_eq!(header.version, format::FORMAT_VERSION);
+    assert_eq!(format::field_name(&header.field), "bn254-fr");
+    assert_eq!(header.num_constraints, r1cs.constraints.len() as u64);
+    assert_eq!(header.num_variables, r1cs.variables.len() as u64);
+
+    let mut bytes = std::fs::read(path("r1cs.bin")).unwrap();
+    *bytes.last_mut().unwrap() ^= 1;
+    std::fs::write(path("corrupt.bin"), &bytes).unwrap();
+    assert!(matches!(R1CS::<Bn254Fr>::load_from_binary(&path("corrupt.bin")), Err(ZkError::ChecksumMismatch)));
+    std::fs::write(path("truncated.bin"), &bytes[..bytes.len() - 1]).unwrap();
+    assert!(matches!(R1CS::<Bn254Fr>::load_from_binary(&path("truncated.bin")), Err(ZkError::Deserialization(_))));
+    assert!(matches!(Proof::load_from_binary(&path("r1cs.bin")), Err(ZkError::Deserialization(_))));
+
+    // A header that lies about the size of an otherwise intact body.
+    let (_, body) = format::read_file(&path("r1cs.bin"), format::R1CS_MAGIC).unwrap();
+    let header = Header::new::<Bn254Fr>(format::R1CS_MAGIC, r1cs.constraints.len() + 1, r1cs.variables.len());
+    format::write_file(&path("miscounted.bin"), header, &body).unwrap();
+    assert!(matches!(R1CS::<Bn254Fr>::load_from_binary(&path("miscounted.bin")), Err(ZkError::Deserialization(_))));
+
+    // A well-formed file whose constraint refers to a wire past the last variable.
+    let mut dangling = eligibility_circuit(3, 4, 5, 23, &[1; 32]).unwrap().to_r1cs().unwrap();
+    let wire = dangling.variables.len();
+    dangling.constraints[0].left[0].0.index = wire;
+    dangling.save_to_binary(&path("dangling.bin")).unwrap();
+    assert!(matches!(R1CS::<Bn254Fr>::load_from_binary(&path("dangling.bin")), Err(ZkError::InvalidWire(index)) if index == wire));
+
+    bytes[4] = 0;
+    std::fs::write(path("old.bin"), &bytes[..HEADER_LEN]).unwrap();
+    assert!(matches!(format::read_header(&path("old.bin")), Err(ZkError::UnsupportedVersion { found: 0, .. })));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn constraint_systems_and_witnesses_round_trip_through_iden3_files() {
+    let dir = temp_dir("iden3");
+    let path = |name: &str| dir.join(name).to_str().unwrap().to_string();
+    let r1cs = eligibility_circuit(50, 40, 90, 3_650, &[3; 32]).unwrap().to_r1cs().unwrap();
+    let witness = r1cs.generate_witness();
+    iden3::write_r1cs(&r1cs, &path("eligibility.r1cs")).unwrap();
+    iden3::write_wtns(&witness, &path("eligibility.wtns")).unwrap();
+
+    let (header, loaded) = iden3::read_r1cs::<Bn254Fr>(&path("eligibility.r1cs")).unwrap();
+    assert_eq!(header.num_wires as usize, r1cs.variables.len());
+    assert_eq!(header.num_constraints as usize, r1cs.constraints.len());
+    assert_eq!(loaded.num_public(), r1cs.num_public());
+    let loaded_witness = iden3::read_wtns::<Bn254Fr>(&path("eligibility.wtns")).unwrap();
+    assert_eq!(loaded_witness, witness);
+    assert!(loaded.verify_witness(&loaded_witness));
+
+    let mut bad = witness.clone();
+    bad[1] += FieldElement::one();
+    assert!(!loaded.verify_witness(&bad));
+    assert!(matches!(
+        iden3::read_wtns::<Goldilocks>(&path("eligibility.wtns")),
+        Err(ZkError::ModulusMismatch { expected, found }) if expected == "goldilocks" && found == "bn254-fr",
+    ));
+    assert!(matches!(iden3::read_r1cs::<Bn254Fr>(&path("eligibility.wtns")), Err(ZkError::Deserialization(_))));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,69 @@
+use zero_knowledge_proofs::circuit::{Circuit, Wire};
+use zero_knowledge_proofs::error::ZkError;
+use zero_knowledge_proofs::field::{Bn254Fr, FieldElement};
+use zero_knowledge_proofs::gadgets;
+
+fn satisfied(cs: &Circuit) -> bool {
+    let r1cs = cs.to_r1cs().unwrap();
+    r1cs.verify_witness(&r1cs.generate_witness())
+}
+
+fn range_checked(value: FieldElement, num_bits: usize) -> bool {
+    let mut cs = Circuit::new();
+    let wire = cs.private_input(value);
+    gadgets::range_check(&mut cs, wire, num_bits).unwrap();
+    satisfied(&cs)
+}
+
+/// Whether a circuit asserting `compare(a, b)` over 8-bit operands is satisfiable.
+fn asserted(compare: fn(&mut Circuit, Wire, Wire, usize) -> Result<Wire, ZkError>, a: u64, b: u64) -> bool {
+    let mut cs = Circuit::new();
+    let a = cs.private_input(FieldElement::from_u64(a));
+    let b = cs.private_input(FieldElement::from_u64(b));
+    let result = compare(&mut cs, a, b, 8).unwrap();
+    gadgets::assert_true(&mut cs, result);
+    satisfied(&cs)
+}
+
+#[test]
+fn range_checks_accept_exactly_the_values_that_fit() {
+    assert!(range_checked(FieldElement::zero(), 8));
+    assert!(range_checked(FieldElement::from_u64(255), 8));
+    assert!(!range_checked(FieldElement::from_u64(256), 8));
+    assert!(range_checked(FieldElement::from_u64(u64::MAX), 64));
+    assert!(!range_checked(FieldElement::from_u64(1), 0));
+    assert!(!range_checked(-FieldElement::one(), 64));
+}
+
+#[test]
+fn comparisons_hold_only_when_true() {
+    for (a, b) in [(0, 0), (0, 1), (1, 0), (7, 7), (254, 255), (255, 254), (0, 255), (255, 0), (255, 255)] {
+        assert_eq!(asserted(gadgets::less_than, a, b), a < b, "{a} < {b}");
+        assert_eq!(asserted(gadgets::less_or_equal, a, b), a <= b, "{a} <= {b}");
+        assert_eq!(asserted(gadgets::greater_than, a, b), a > b, "{a} > {b}");
+        assert_eq!(asserted(gadgets::greater_or_equal, a, b), a >= b, "{a} >= {b}");
+    }
+}
+
+#[test]
+fn assert_true_rejects_false() {
+    for (value, holds) in [(0, false), (1, true), (2, false)] {
+        let mut cs: Circuit = Circuit::new();
+        let wire = cs.private_input(FieldElement::from_u64(value));
+        gadgets::assert_true(&mut cs, wire);
+        assert_eq!(satisfied(&cs), holds, "{value}");
+    }
+}
+
+#[test]
+fn widths_beyond_the_field_are_invalid_parameters() {
+    let capacity = gadgets::capacity::<Bn254Fr>();
+    let mut cs: Circuit = Circuit::new();
+    let (a, b) = (cs.private_input(FieldElement::one()), cs.private_input(FieldElement::zero()));
+
+    assert!(gadgets::range_check(&mut cs, a, capacity).is_ok());
+    assert!(matches!(gadgets::range_check(&mut cs, a, capacity + 1), Err(ZkError::InvalidParameters(_))));
+    assert!(gadgets::less_than(&mut cs, a, b, capacity - 1).is_ok());
+    assert!(matches!(gadgets::less_than(&mut cs, a, b, capacity), Err(ZkError::InvalidParameters(_))));
+    assert!(matches!(gadgets::greater_or_equal(&mut cs, a, b, capacity), Err(ZkError::InvalidParameters(_))));
+}
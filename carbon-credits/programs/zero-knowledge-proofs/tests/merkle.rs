@@ -0,0 +1,54 @@
+use num_bigint::BigInt;
+use zero_knowledge_proofs::circuit::Circuit;
+use zero_knowledge_proofs::field::FieldElement;
+use zero_knowledge_proofs::merkle::{self, MerkleTree};
+
+fn membership_circuit(leaf: &BigInt, path: &[(BigInt, bool)], root: &BigInt) -> bool {
+    let mut cs = Circuit::new();
+    let root = cs.public_input(FieldElement::new(root.clone()));
+    let leaf = cs.private_input(FieldElement::new(leaf.clone()));
+    let path = merkle::PathWires::private(&mut cs, path);
+    merkle::membership_gadget(&mut cs, leaf, &path, root);
+
+    let r1cs = cs.to_r1cs().unwrap();
+    r1cs.verify_witness(&r1cs.generate_witness())
+}
+
+#[test]
+fn every_leaf_proves_membership() {
+    let leaves: Vec<BigInt> = (1..=5).map(BigInt::from).collect();
+    let tree = MerkleTree::new(leaves.clone());
+    assert_eq!(tree.depth(), 3);
+    for (index, leaf) in leaves.iter().enumerate() {
+        let path = tree.merkle_path(index);
+        assert_eq!(path.len(), tree.depth(), "path of leaf {index}");
+        assert!(MerkleTree::verify_path(&tree.root, leaf, &path));
+        assert!(membership_circuit(leaf, &path, &tree.root));
+    }
+}
+
+#[test]
+fn paths_of_uneven_trees_do_not_reveal_the_leaf_position() {
+    for (size, depth) in [(1, 0), (3, 2), (6, 3), (7, 3)] {
+        let leaves: Vec<BigInt> = (10..10 + size).map(BigInt::from).collect();
+        let tree = MerkleTree::new(leaves.clone());
+        for (index, leaf) in leaves.iter().enumerate() {
+            let path = tree.merkle_path(index);
+            assert_eq!(path.len(), depth, "leaf {index} of {size}");
+            assert!(MerkleTree::verify_path(&tree.root, leaf, &path), "leaf {index} of {size}");
+        }
+    }
+}
+
+#[test]
+fn wrong_root_leaf_or_direction_is_rejected() {
+    let leaves: Vec<BigInt> = (1..=4).map(BigInt::from).collect();
+    let tree = MerkleTree::new(leaves.clone());
+    let mut path = tree.merkle_path(2);
+
+    assert!(!membership_circuit(&leaves[2], &path, &(tree.root.clone() + 1)));
+    assert!(!membership_circuit(&leaves[1], &path, &tree.root));
+    path[0].1 = !path[0].1;
+    assert!(!MerkleTree::verify_path(&tree.root, &leaves[2], &path));
+    assert!(!membership_circuit(&leaves[2], &path, &tree.root));
+}
@@ -0,0 +1,67 @@
+use zero_knowledge_proofs::error::ZkError;
+use zero_knowledge_proofs::field::{Bls12_381Fr, Bn254Fr, FieldElement, FieldParams, Goldilocks};
+use zero_knowledge_proofs::poly::{EvaluationDomain, Polynomial};
+
+fn polynomial<F: FieldParams>(len: u64, seed: u64) -> Polynomial<F> {
+    Polynomial::new((0..len).map(|i| FieldElement::from_u64(i * i * seed + 3 * i + seed)).collect())
+}
+
+fn ntt_properties<F: FieldParams>() {
+    let two_adicity = FieldElement::<F>::two_adicity();
+    let root = FieldElement::<F>::root_of_unity(two_adicity).unwrap();
+    let half_turn = (1..two_adicity).fold(root, |power, _| power.square());
+    assert_eq!(half_turn, -FieldElement::one(), "{}: root of unity is not primitive", F::NAME);
+    assert!(FieldElement::<F>::root_of_unity(two_adicity + 1).is_none());
+
+    assert!(matches!(
+        EvaluationDomain::<F>::new((1 << two_adicity) + 1),
+        Err(ZkError::DomainSize { available, .. }) if available == 1 << two_adicity,
+    ));
+
+    let domain = EvaluationDomain::<F>::new(100).unwrap();
+    assert_eq!(domain.size(), 128);
+    let p = polynomial::<F>(90, 7);
+
+    let evaluations = domain.fft(p.coefficients()).unwrap();
+    for (x, y) in domain.elements().iter().zip(&evaluations) {
+        assert_eq!(p.evaluate(x), *y);
+    }
+    assert_eq!(domain.interpolate(&evaluations).unwrap(), p);
+    assert!(matches!(domain.fft(polynomial::<F>(129, 1).coefficients()), Err(ZkError::DomainSize { .. })));
+
+    let coset = domain.coset_fft(p.coefficients()).unwrap();
+    for (x, y) in domain.elements().iter().zip(&coset) {
+        assert_eq!(p.evaluate(&(*x * domain.coset_offset())), *y);
+    }
+    assert_eq!(Polynomial::new(domain.coset_ifft(&coset).unwrap()), p);
+
+    let x = domain.generator();
+    assert!(domain.evaluate_vanishing(&x).is_zero());
+    assert!(domain.vanishing_polynomial().evaluate(&x).is_zero());
+}
+
+#[test]
+fn ntt_over_every_field() {
+    ntt_properties::<Bn254Fr>();
+    ntt_properties::<Bls12_381Fr>();
+    ntt_properties::<Goldilocks>();
+}
+
+#[test]
+fn ntt_multiplication_matches_evaluation() {
+    // Both factors are above the size where multiplication switches to NTTs.
+    let p = polynomial::<Bn254Fr>(150, 5);
+    let q = polynomial::<Bn254Fr>(80, 11);
+    let product = &p * &q;
+    assert_eq!(product.degree(), Some(149 + 79));
+    for x in [0, 1, 2, 12345] {
+        let x = FieldElement::from_u64(x);
+        assert_eq!(product.evaluate(&x), p.evaluate(&x) * q.evaluate(&x));
+    }
+
+    let (quotient, remainder) = (&product + &Polynomial::constant(FieldElement::from_u64(9))).div_rem(&q).unwrap();
+    assert_eq!(quotient, p);
+    assert_eq!(remainder, Polynomial::constant(FieldElement::from_u64(9)));
+    assert!((&p - &p).is_zero());
+    assert!(matches!(p.div_rem(&Polynomial::zero()), Err(ZkError::NoInverse)));
+}
@@ -0,0 +1,79 @@
+use proptest::prelude::*;
+use zero_knowledge_proofs::circuit::Circuit;
+use zero_knowledge_proofs::field::{FieldElement, Goldilocks};
+use zero_knowledge_proofs::poseidon::{self, Poseidon};
+use zero_knowledge_proofs::r1cs::R1CS;
+
+fn from_hex(hex: &str) -> FieldElement {
+    let mut bytes = [0u8; 32];
+    for (i, byte) in bytes.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&hex[2 * i..2 * i + 2], 16).unwrap();
+    }
+    FieldElement::from_bytes_be(&bytes).unwrap()
+}
+
+/// Reference outputs of circomlibjs' `poseidon`.
+#[test]
+fn matches_circomlib() {
+    let one = FieldElement::from_u64(1);
+    let two = FieldElement::from_u64(2);
+    assert_eq!(
+        poseidon::hash(&[one]),
+        from_hex("29176100eaa962bdc1fe6c654d6a3c130e96a4d1168b33848b897dc502820133"),
+    );
+    assert_eq!(
+        poseidon::hash(&[one, two]),
+        from_hex("115cc0f5e7d690413df64c6b9662e9cf2a3617f2743245519e19607a4417189a"),
+    );
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(16))]
+
+    #[test]
+    fn gadget_matches_native(values in prop::collection::vec(any::<u64>(), 1..=4)) {
+        let inputs: Vec<FieldElement> = values.iter().map(|value| FieldElement::from_u64(*value)).collect();
+        let hasher = Poseidon::circom(inputs.len()).unwrap();
+        let expected = hasher.hash(&inputs).unwrap();
+
+        let mut cs = Circuit::new();
+        let wires: Vec<_> = inputs.iter().map(|input| cs.private_input(*input)).collect();
+        let digest = hasher.hash_gadget(&mut cs, &wires).unwrap();
+        prop_assert_eq!(cs.value(digest), expected);
+        let r1cs = cs.to_r1cs().unwrap();
+        prop_assert!(r1cs.verify_witness(&r1cs.generate_witness()));
+
+        let mut r1cs: R1CS = R1CS::new();
+        let variables: Vec<usize> = inputs.iter().map(|input| r1cs.add_variable(*input)).collect();
+        let digest = r1cs.add_hash(&variables).unwrap();
+        prop_assert_eq!(r1cs.variables[digest].value, expected);
+        prop_assert!(r1cs.verify_witness(&r1cs.generate_witness()));
+    }
+
+    #[test]
+    fn gadget_matches_native_over_goldilocks(a in any::<u64>(), b in any::<u64>()) {
+        let hasher = Poseidon::<Goldilocks>::new(3, 7, 8, 22).unwrap();
+        let inputs = [FieldElement::from_u64(a), FieldElement::from_u64(b)];
+
+        let mut cs = Circuit::new();
+        let wires = inputs.map(|input| cs.private_input(input));
+        let digest = hasher.hash_gadget(&mut cs, &wires).unwrap();
+        prop_assert_eq!(cs.value(digest), hasher.hash(&inputs).unwrap());
+        let r1cs = cs.to_r1cs().unwrap();
+        prop_assert!(r1cs.verify_witness(&r1cs.generate_witness()));
+    }
+}
+
+#[test]
+fn tampered_digest_is_rejected() {
+    let mut cs = Circuit::new();
+    let a = cs.private_input(FieldElement::from_u64(1));
+    let b = cs.private_input(FieldElement::from_u64(2));
+    let digest = poseidon::hash_gadget(&mut cs, &[a, b]);
+    cs.expose(digest);
+
+    let r1cs = cs.to_r1cs().unwrap();
+    let mut witness = r1cs.generate_witness();
+    witness[1] += FieldElement::one();
+    assert!(!r1cs.verify_witness(&witness));
+}
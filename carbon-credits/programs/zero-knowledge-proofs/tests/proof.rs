@@ -0,0 +1,42 @@
+use zero_knowledge_proofs::circuit::Circuit;
+use zero_knowledge_proofs::field::FieldElement;
+use zero_knowledge_proofs::proof::{self, Proof};
+use zero_knowledge_proofs::setup;
+
+fn product_circuit(a: u64, b: u64) -> Circuit {
+    let mut cs = Circuit::new();
+    let a = cs.private_input(FieldElement::from_u64(a));
+    let b = cs.private_input(FieldElement::from_u64(b));
+    let product = cs.mul(a, b);
+    cs.expose(product);
+    cs
+}
+
+#[test]
+fn proofs_stay_in_memory_and_do_not_interfere() {
+    let first = proof::prove(&product_circuit(3, 4)).unwrap();
+    let second = proof::prove(&product_circuit(5, 6)).unwrap();
+    assert_eq!(first.public_inputs, vec![FieldElement::from_u64(12)]);
+
+    assert!(proof::verify(&first.verifying_key, &first.public_inputs, &first.proof));
+    assert!(proof::verify(&second.verifying_key, &second.public_inputs, &second.proof));
+    assert!(!proof::verify(&first.verifying_key, &second.public_inputs, &first.proof));
+    assert!(!proof::verify(&first.verifying_key, &first.public_inputs, &second.proof));
+    assert!(!proof::verify(&first.verifying_key[1..], &first.public_inputs, &first.proof));
+}
+
+#[test]
+fn artifacts_persist_to_caller_chosen_paths() {
+    let artifacts = proof::prove(&product_circuit(7, 8)).unwrap();
+    let dir = std::env::temp_dir().join(format!("zkp-artifacts-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = |name: &str| dir.join(name).to_str().unwrap().to_string();
+
+    artifacts.save_proof(&path("proof.bin")).unwrap();
+    artifacts.save_verifying_key(&path("vk.bin")).unwrap();
+    let proof = Proof::load_from_binary(&path("proof.bin")).unwrap();
+    let vk = setup::load_verifying_key(&path("vk.bin")).unwrap();
+    assert!(Proof::verify_proof(&proof, &vk));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
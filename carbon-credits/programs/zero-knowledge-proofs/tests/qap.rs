@@ -0,0 +1,57 @@
+use zero_knowledge_proofs::eligibility_circuit;
+use zero_knowledge_proofs::error::ZkError;
+use zero_knowledge_proofs::field::{Bn254Fr, FieldElement};
+use zero_knowledge_proofs::poly::Polynomial;
+
+#[test]
+fn interpolation_passes_through_every_point() {
+    let points: Vec<(FieldElement, FieldElement)> = [(1, 5), (2, 11), (7, 3), (9, 0)]
+        .iter()
+        .map(|(x, y)| (FieldElement::from_u64(*x), FieldElement::from_u64(*y)))
+        .collect();
+    let polynomial = Polynomial::interpolate(&points).unwrap();
+    assert!(polynomial.degree() < Some(points.len()));
+    for (x, y) in &points {
+        assert_eq!(polynomial.evaluate(x), *y);
+    }
+
+    let repeated = [points[0], points[1], points[0]];
+    assert!(matches!(Polynomial::interpolate(&repeated), Err(ZkError::NoInverse)));
+}
+
+#[test]
+fn division_with_remainder_recombines() {
+    let coefficients =
+        |values: &[u64]| Polynomial::<Bn254Fr>::new(values.iter().map(|value| FieldElement::from_u64(*value)).collect());
+    let dividend = coefficients(&[3, 0, 2, 5]);
+    let divisor = coefficients(&[1, 4]);
+    let (quotient, remainder) = dividend.div_rem(&divisor).unwrap();
+    assert_eq!(quotient.mul(&divisor).add(&remainder), dividend);
+    assert!(remainder.degree() < divisor.degree());
+}
+
+#[test]
+fn quotient_exists_only_for_satisfying_witnesses() {
+    let r1cs = eligibility_circuit(3, 4, 5, 23, &[1; 32]).unwrap().to_r1cs().unwrap();
+    let qap = r1cs.to_qap().unwrap();
+    let witness = r1cs.generate_witness();
+
+    let quotient = qap.quotient(&witness).unwrap();
+    let (a, b, c) = qap.witness_polynomials(&witness).unwrap();
+    assert_eq!(quotient.mul(&qap.vanishing_polynomial()), a.mul(&b).sub(&c));
+
+    let weighted = witness.iter().enumerate().fold(Polynomial::zero(), |sum, (index, value)| {
+        let (a_i, _, _) = qap.variable_polynomials(index).unwrap();
+        sum.add(&a_i.scale(value))
+    });
+    assert_eq!(weighted, a);
+
+    let mut tampered = witness.clone();
+    tampered[1] += FieldElement::one();
+    assert!(matches!(qap.quotient(&tampered), Err(ZkError::Unsatisfied)));
+    assert!(matches!(
+        qap.quotient(&witness[1..]),
+        Err(ZkError::LengthMismatch { expected, actual }) if expected == witness.len() && actual == witness.len() - 1,
+    ));
+    assert!(matches!(qap.variable_polynomials(witness.len()), Err(ZkError::InvalidWire(_))));
+}
@@ -0,0 +1,158 @@
+use num_bigint::BigUint;
+use rand::rngs::OsRng;
+use zero_knowledge_proofs::circuit::Circuit;
+use zero_knowledge_proofs::error::ZkError;
+use zero_knowledge_proofs::field::FieldElement;
+use zero_knowledge_proofs::r1cs::R1CS;
+use zero_knowledge_proofs::{format, groth16, iden3};
+use zero_knowledge_proofs::setup::{self, Ceremony, PowersOfTau};
+
+/// `base^2 * exponent` with a public base and result: small enough for a 16-point domain.
+fn pow_circuit() -> R1CS {
+    let mut cs = Circuit::new();
+    let base = cs.public_input(FieldElement::from_u64(3));
+    let exponent = cs.private_input(FieldElement::from_u64(5));
+    let square = cs.mul(base, base);
+    let product = cs.mul(square, exponent);
+    cs.expose(product);
+    cs.to_r1cs().unwrap()
+}
+
+fn temp_dir(name: &str) -> std::path::PathBuf {
+    let dir = std::env::temp_dir().join(format!("zkp-{name}-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[test]
+fn ceremony_from_powers_of_tau_produces_working_keys() {
+    let r1cs = pow_circuit();
+    let witness = r1cs.generate_witness();
+    let powers = PowersOfTau::generate(16, &mut OsRng).unwrap();
+    powers.verify().unwrap();
+
+    let mut ceremony = Ceremony::from_powers_of_tau(&r1cs, &powers).unwrap();
+    assert!(matches!(ceremony.verify(&r1cs, &powers), Err(ZkError::InvalidParameters(_))));
+    let first = ceremony.contribute(&mut OsRng).unwrap();
+    let second = ceremony.contribute(&mut OsRng).unwrap();
+    assert_eq!(ceremony.verify(&r1cs, &powers).unwrap(), vec![first, second]);
+
+    let proof = groth16::prove(ceremony.proving_key(), &r1cs, &witness, &mut OsRng).unwrap();
+    let inputs = r1cs.public_inputs(&witness).unwrap();
+    assert!(groth16::verify(ceremony.verifying_key(), inputs, &proof));
+
+    let too_small = PowersOfTau::generate(2, &mut OsRng).unwrap();
+    assert!(matches!(Ceremony::from_powers_of_tau(&r1cs, &too_small), Err(ZkError::DomainSize { .. })));
+
+    let mut forged = powers.clone();
+    forged.beta_tau_g1.swap(1, 2);
+    assert!(matches!(Ceremony::from_powers_of_tau(&r1cs, &forged), Err(ZkError::InvalidParameters(_))));
+}
+
+#[test]
+fn powers_of_tau_round_trip_through_ptau_files() {
+    let dir = temp_dir("ptau");
+    let path = |name: &str| dir.join(name).to_str().unwrap().to_string();
+    let powers = PowersOfTau::generate(16, &mut OsRng).unwrap();
+    iden3::write_ptau(&powers, &path("powers.ptau")).unwrap();
+
+    // snarkjs stores coordinates in Montgomery form: the generator (1, 2) as (R, 2R) mod q.
+    let q: BigUint = "21888242871839275222246405745257275088696311157297823662689037894645226208583".parse().unwrap();
+    let montgomery = |value: u32| {
+        let mut bytes = ((BigUint::from(value) << 256u32) % &q).to_bytes_le();
+        bytes.resize(32, 0);
+        bytes
+    };
+    let bytes = std::fs::read(path("powers.ptau")).unwrap();
+    let generator = [montgomery(1), montgomery(2)].concat();
+    assert_eq!(&bytes[..4], b"ptau");
+    assert!(bytes.windows(64).any(|window| window == generator.as_slice()));
+
+    let read = iden3::read_ptau(&path("powers.ptau"), 16).unwrap();
+    assert_eq!(groth16::to_bytes(&read).unwrap(), groth16::to_bytes(&powers).unwrap());
+    let prefix = iden3::read_ptau(&path("powers.ptau"), 8).unwrap();
+    prefix.verify().unwrap();
+    assert_eq!(prefix.tau_g1[..], powers.tau_g1[..15]);
+    assert!(Ceremony::from_powers_of_tau(&pow_circuit(), &prefix).is_ok());
+    assert!(matches!(iden3::read_ptau(&path("powers.ptau"), 32), Err(ZkError::DomainSize { required: 32, available: 16 })));
+
+    // Flip a bit of the second tau point, which takes it off the curve.
+    let offset = bytes.windows(64).position(|window| window == generator.as_slice()).unwrap() + 64;
+    let mut corrupt = bytes.clone();
+    corrupt[offset] ^= 1;
+    std::fs::write(path("corrupt.ptau"), corrupt).unwrap();
+    assert!(matches!(iden3::read_ptau(&path("corrupt.ptau"), 16), Err(ZkError::Deserialization(_))));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn tampered_ceremonies_are_rejected() {
+    let r1cs = pow_circuit();
+    let powers = PowersOfTau::generate(16, &mut OsRng).unwrap();
+    let other = PowersOfTau::generate(16, &mut OsRng).unwrap();
+    let contribute = |powers: &PowersOfTau| {
+        let mut ceremony = Ceremony::from_powers_of_tau(&r1cs, powers).unwrap();
+        ceremony.contribute(&mut OsRng).unwrap();
+        ceremony
+    };
+    let ceremony = contribute(&powers);
+    assert!(matches!(ceremony.verify(&r1cs, &other), Err(ZkError::InvalidParameters(_))));
+    assert!(contribute(&other).verify(&r1cs, &powers).is_err());
+
+    // Keys from one ceremony under the contributions of another, from the same start.
+    let dir = temp_dir("ceremony");
+    let path = |name: &str| dir.join(name).to_str().unwrap().to_string();
+    ceremony.save(&path("ceremony.bin")).unwrap();
+    contribute(&powers).save(&path("fork.bin")).unwrap();
+    assert_eq!(Ceremony::load(&path("ceremony.bin")).unwrap().verify(&r1cs, &powers).unwrap().len(), 1);
+
+    let key_len = groth16::to_bytes(ceremony.proving_key()).unwrap().len();
+    let (header, mut spliced) = format::read_file(&path("ceremony.bin"), setup::CEREMONY_MAGIC).unwrap();
+    let (_, fork) = format::read_file(&path("fork.bin"), setup::CEREMONY_MAGIC).unwrap();
+    spliced[..key_len].copy_from_slice(&fork[..key_len]);
+    format::write_file(&path("spliced.bin"), header, &spliced).unwrap();
+    assert!(Ceremony::load(&path("spliced.bin")).unwrap().verify(&r1cs, &powers).is_err());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn key_files_are_versioned() {
+    let (pk, vk) = groth16::setup(&pow_circuit(), &mut OsRng).unwrap();
+    let dir = temp_dir("keys");
+    let path = |name: &str| dir.join(name).to_str().unwrap().to_string();
+
+    setup::save_proving_key(&pk, &path("pk.bin")).unwrap();
+    setup::save_verifying_key(&vk, &path("vk.bin")).unwrap();
+    assert_eq!(setup::load_verifying_key(&path("vk.bin")).unwrap(), vk);
+    assert_eq!(setup::load_proving_key(&path("pk.bin")).unwrap(), pk);
+    assert!(matches!(setup::load_proving_key(&path("vk.bin")), Err(ZkError::Deserialization(_))));
+
+    // A key without even the constant-one point has no public input count to record.
+    let mut empty = vk.clone();
+    empty.gamma_abc_g1.clear();
+    assert!(matches!(setup::save_verifying_key(&empty, &path("empty.bin")), Err(ZkError::Deserialization(_))));
+    let header = format::Header::new::<zero_knowledge_proofs::field::Bn254Fr>(setup::VERIFYING_KEY_MAGIC, 0, 0);
+    format::write_file(&path("empty.bin"), header, &groth16::to_bytes(&empty).unwrap()).unwrap();
+    assert!(matches!(setup::load_verifying_key(&path("empty.bin")), Err(ZkError::Deserialization(_))));
+
+    let mut bytes = std::fs::read(path("vk.bin")).unwrap();
+    bytes[4] = 2;
+    std::fs::write(path("vk.bin"), bytes).unwrap();
+    assert!(matches!(
+        setup::load_verifying_key(&path("vk.bin")),
+        Err(ZkError::UnsupportedVersion { found: 2, supported: format::FORMAT_VERSION })
+    ));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[cfg(feature = "dev-keys")]
+#[test]
+fn on_chain_development_key_matches_the_development_keys() {
+    let (_, vk) = zero_knowledge_proofs::dev_eligibility_keys().unwrap();
+    let exported = setup::export_verifying_key(&vk, "ELIGIBILITY_DEV_VERIFYING_KEY");
+    let embedded = include_str!("../../carbon-credits/src/eligibility_dev_vk.rs");
+    assert!(embedded.ends_with(&exported), "regenerate eligibility_dev_vk.rs with setup::export_verifying_key");
+}
@@ -0,0 +1,102 @@
+use zero_knowledge_proofs::error::ZkError;
+use zero_knowledge_proofs::field::FieldElement;
+use std::collections::HashMap;
+use zero_knowledge_proofs::sparse_merkle::{self, Key, SparseMerkleTree};
+
+fn key(byte: u8) -> Key {
+    let mut key = [0u8; 32];
+    key[0] = byte;
+    key[31] = byte.wrapping_mul(31);
+    key
+}
+
+#[test]
+fn insert_update_and_delete_track_the_root() {
+    let mut tree = SparseMerkleTree::new();
+    let empty_root = tree.root();
+
+    tree.insert(key(1), FieldElement::from_u64(10)).unwrap();
+    tree.insert(key(2), FieldElement::from_u64(20)).unwrap();
+    assert!(matches!(tree.insert(key(1), FieldElement::from_u64(11)), Err(ZkError::KeyExists)));
+    let two_leaves = tree.root();
+
+    assert_eq!(tree.update(&key(1), FieldElement::from_u64(11)).unwrap(), FieldElement::from_u64(10));
+    assert_ne!(tree.root(), two_leaves);
+    assert!(matches!(tree.update(&key(3), FieldElement::one()), Err(ZkError::KeyNotFound)));
+
+    // The root only depends on the contents, not on the order of operations.
+    let mut rebuilt = SparseMerkleTree::new();
+    rebuilt.insert(key(2), FieldElement::from_u64(20)).unwrap();
+    rebuilt.insert(key(1), FieldElement::from_u64(11)).unwrap();
+    assert_eq!(rebuilt.root(), tree.root());
+
+    assert_eq!(tree.delete(&key(1)).unwrap(), FieldElement::from_u64(11));
+    assert_eq!(tree.delete(&key(2)).unwrap(), FieldElement::from_u64(20));
+    assert!(matches!(tree.delete(&key(2)), Err(ZkError::KeyNotFound)));
+    assert_eq!(tree.root(), empty_root);
+    assert!(tree.is_empty());
+}
+
+#[test]
+fn membership_and_non_membership_proofs() {
+    let mut tree = SparseMerkleTree::new();
+    for byte in 1..=8 {
+        tree.insert(key(byte), FieldElement::from_u64(byte as u64)).unwrap();
+    }
+    let root = tree.root();
+
+    for byte in 1..=8 {
+        let proof = tree.prove(&key(byte));
+        let value = tree.get(&key(byte)).unwrap();
+        assert_eq!(proof.siblings.len(), sparse_merkle::DEPTH);
+        assert!(proof.verify_membership(&root, &key(byte), &value));
+        assert!(!proof.verify_membership(&root, &key(byte), &(value + FieldElement::one())));
+        assert!(!proof.verify_non_membership(&root, &key(byte)));
+    }
+
+    for byte in 100..=108 {
+        let proof = tree.prove(&key(byte));
+        assert!(proof.verify_non_membership(&root, &key(byte)));
+        assert!(!proof.verify_membership(&root, &key(byte), &FieldElement::one()));
+        assert!(!proof.verify_non_membership(&(root + FieldElement::one()), &key(byte)));
+    }
+}
+
+#[test]
+fn keys_whose_hashes_share_low_bits_coexist() {
+    // Two keys whose hashes agree on the low 16 bits, which used to put them in the same slot.
+    let mut seen = HashMap::new();
+    let (first, second) = (0u16..)
+        .map(|counter| {
+            let mut key = [0u8; 32];
+            key[..2].copy_from_slice(&counter.to_le_bytes());
+            key
+        })
+        .find_map(|key| {
+            let low = u16::from_le_bytes(sparse_merkle::key_hash(&key).to_bytes_le()[..2].try_into().unwrap());
+            seen.insert(low, key).map(|earlier| (earlier, key))
+        })
+        .unwrap();
+
+    let mut tree = SparseMerkleTree::new();
+    tree.insert(first, FieldElement::from_u64(1)).unwrap();
+    tree.insert(second, FieldElement::from_u64(2)).unwrap();
+    let root = tree.root();
+    assert!(tree.prove(&first).verify_membership(&root, &first, &FieldElement::from_u64(1)));
+    assert!(tree.prove(&second).verify_membership(&root, &second, &FieldElement::from_u64(2)));
+
+    tree.delete(&first).unwrap();
+    assert!(tree.prove(&first).verify_non_membership(&tree.root(), &first));
+    assert_eq!(tree.get(&second), Some(FieldElement::from_u64(2)));
+}
+
+#[test]
+fn state_round_trips_through_bincode() {
+    let mut tree = SparseMerkleTree::new();
+    tree.insert(key(7), FieldElement::from_u64(70)).unwrap();
+
+    let bytes = bincode::serialize(&tree).unwrap();
+    let restored: SparseMerkleTree = bincode::deserialize(&bytes).unwrap();
+    assert_eq!(restored.root_bytes(), tree.root_bytes());
+    assert_eq!(restored.get(&key(7)), Some(FieldElement::from_u64(70)));
+}